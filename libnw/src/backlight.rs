@@ -1,3 +1,5 @@
+use crate::time::{self, Duration, Instant};
+
 pub use eadk::brightness as get_brightness;
 pub use eadk::set_brightness;
 
@@ -9,6 +11,176 @@ pub const MAX_BRIGHTNESS: u8 = 240;
 /// Even though the brightness is stored in a u8, only 16 levels of brightness are available.
 pub const BRIGHTNESS_INCREMENT: u8 = 16;
 
+/// A brightness the screen can show, from level 0 (off) to 15.
+///
+/// The raw brightness of a level is `level * BRIGHTNESS_INCREMENT`, up to
+/// [`MAX_BRIGHTNESS`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Brightness(u8);
+
+impl Brightness {
+    /// The screen is off.
+    pub const OFF: Self = Self(0);
+    /// The brightest level, 15.
+    pub const MAX: Self = Self(MAX_BRIGHTNESS / BRIGHTNESS_INCREMENT);
+
+    /// Returns `None` if the level is above 15.
+    pub const fn new(level: u8) -> Option<Self> {
+        if level <= Self::MAX.0 {
+            Some(Self(level))
+        } else {
+            None
+        }
+    }
+
+    /// Clamps the level to 15.
+    pub const fn saturating(level: u8) -> Self {
+        if level <= Self::MAX.0 {
+            Self(level)
+        } else {
+            Self::MAX
+        }
+    }
+
+    /// Rounds down a raw brightness, as returned by [`get_brightness`].
+    pub const fn from_raw(raw: u8) -> Self {
+        Self::saturating(raw / BRIGHTNESS_INCREMENT)
+    }
+
+    /// The level, from 0 to 15.
+    pub const fn level(self) -> u8 {
+        self.0
+    }
+
+    /// The brightness to give to [`set_brightness`].
+    pub const fn raw(self) -> u8 {
+        self.0 * BRIGHTNESS_INCREMENT
+    }
+
+    /// One level up, if not already at the maximum.
+    pub const fn brighter(self) -> Self {
+        Self::saturating(self.0 + 1)
+    }
+
+    /// One level down, if not already off.
+    pub const fn dimmer(self) -> Self {
+        Self(self.0.saturating_sub(1))
+    }
+}
+
+/// Returns the brightness of the screen, rounded down to a level.
+pub fn get_level() -> Brightness {
+    Brightness::from_raw(get_brightness())
+}
+
+/// Sets the brightness of the screen.
+pub fn set_level(brightness: Brightness) {
+    set_brightness(brightness.raw());
+}
+
+/// Fades the brightness to `target` over `duration`, blocking until it is done.
+pub fn fade_to(target: Brightness, duration: Duration) {
+    let mut fade = Fade::new(target, duration);
+    while fade.update() {
+        time::sleep(fade.step_duration());
+    }
+}
+
+/// A fade of the brightness going on while the app keeps running.
+///
+/// ```ignore
+/// let mut fade = Fade::new(Brightness::OFF, Duration::from_millis(500));
+/// while fade.update() {
+///     draw_frame();
+/// }
+/// ```
+pub struct Fade {
+    from: Brightness,
+    target: Brightness,
+    start: Instant,
+    duration: Duration,
+}
+
+impl Fade {
+    /// Starts a fade from the current brightness.
+    pub fn new(target: Brightness, duration: Duration) -> Self {
+        Self {
+            from: get_level(),
+            target,
+            start: Instant::now(),
+            duration,
+        }
+    }
+
+    /// The brightness reached at the end of the fade.
+    pub fn target(&self) -> Brightness {
+        self.target
+    }
+
+    /// Whether the duration of the fade is over.
+    pub fn is_done(&self) -> bool {
+        self.start.elapsed() >= self.duration
+    }
+
+    /// Sets the brightness for the time elapsed since the start, returns false
+    /// once the target is reached.
+    pub fn update(&mut self) -> bool {
+        let elapsed = self.start.elapsed();
+        if elapsed >= self.duration {
+            set_level(self.target);
+            return false;
+        }
+        let from = self.from.level() as i64;
+        let span = self.target.level() as i64 - from;
+        let progress = span * elapsed.as_millis() as i64 / self.duration.as_millis().max(1) as i64;
+        set_level(Brightness::saturating((from + progress) as u8));
+        true
+    }
+
+    /// The time between two levels of the fade.
+    fn step_duration(&self) -> Duration {
+        let steps = self.from.level().abs_diff(self.target.level()).max(1);
+        self.duration / steps as u32
+    }
+}
+
+/// Puts back the brightness found when it was created once it is dropped, to
+/// leave the screen as the user set it when the app exits.
+///
+/// ```ignore
+/// let _brightness = BrightnessGuard::new();
+/// backlight::set_level(Brightness::MAX);
+/// ```
+pub struct BrightnessGuard {
+    original: u8,
+}
+
+impl BrightnessGuard {
+    /// Saves the current brightness.
+    pub fn new() -> Self {
+        Self {
+            original: get_brightness(),
+        }
+    }
+
+    /// The brightness put back when the guard is dropped.
+    pub fn original(&self) -> Brightness {
+        Brightness::from_raw(self.original)
+    }
+}
+
+impl Default for BrightnessGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for BrightnessGuard {
+    fn drop(&mut self) {
+        set_brightness(self.original);
+    }
+}
+
 /// Interface with the raw `eadk` C api.
 ///
 /// If you don't know what you are doing, use the safe rust implementations.
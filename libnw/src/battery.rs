@@ -2,6 +2,37 @@ pub use eadk::is_charging;
 pub use eadk::level as get_level;
 pub use eadk::voltage as get_voltage;
 
+/// How full the battery is, in the steps shown by the firmware.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Charge {
+    Empty,
+    Low,
+    Somewhat,
+    Full,
+}
+
+impl Charge {
+    /// Converts a level returned by [`get_level`], from 0 to 3.
+    pub fn from_level(level: u8) -> Self {
+        match level {
+            0 => Self::Empty,
+            1 => Self::Low,
+            2 => Self::Somewhat,
+            _ => Self::Full,
+        }
+    }
+
+    /// The level of the charge, from 0 to 3.
+    pub fn level(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Returns how full the battery is.
+pub fn get_charge() -> Charge {
+    Charge::from_level(get_level())
+}
+
 /// Interface with the raw `eadk` C api.
 ///
 /// If you don't know what you are doing, use the safe rust implementations.
@@ -0,0 +1,651 @@
+extern crate alloc;
+
+use alloc::{format, string::String, vec::Vec};
+use core::f32::consts::TAU;
+
+use crate::display::{self, Buffer, CHAR_HEIGHT, CHAR_WIDTH, Color, Rect, eadk};
+use crate::plot::{format_number, tick_step, ticks};
+use crate::theme;
+
+/// Distinct colors for the data sets and slices, in the order of the firmware apps.
+pub const PALETTE: [Color; 8] = [
+    Color::from_rgb(0xFF, 0x00, 0x0C),
+    Color::from_rgb(0x05, 0x0E, 0xFF),
+    Color::from_rgb(0x46, 0xBA, 0x3D),
+    Color::from_rgb(0xFE, 0x87, 0x1F),
+    Color::from_rgb(0x93, 0x27, 0xFF),
+    Color::from_rgb(0x26, 0xBE, 0xE4),
+    Color::from_rgb(0xFF, 0x7C, 0xB3),
+    Color::from_rgb(0x8D, 0x73, 0x50),
+];
+
+/// The space around the texts and between the parts of a chart, in pixels.
+const MARGIN: u16 = 3;
+/// The size of the colored squares of the legend, in pixels.
+const SWATCH_SIZE: u16 = 8;
+/// The size of the squares on the points of a line chart, in pixels.
+const MARKER_SIZE: u16 = 3;
+/// The number of ticks aimed for on the value axis.
+const TARGET_TICKS: f32 = 4.0;
+
+/// Something charts can be drawn on: the screen or an off-screen [`Buffer`].
+///
+/// The labels drawn in a [`Buffer`] still flash in the top left corner of the
+/// screen, see [`Buffer::draw_string`].
+pub trait Canvas {
+    fn fill_rect(&mut self, rect: Rect, color: Color);
+
+    /// Draws a one pixel wide line, both ends included.
+    fn draw_line(&mut self, x0: u16, y0: u16, x1: u16, y1: u16, color: Color);
+
+    /// Draws a string with the small font.
+    fn draw_string(&mut self, text: &str, x: u16, y: u16, color: Color, background: Color);
+}
+
+/// Draws directly on the screen.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Screen;
+
+impl Canvas for Screen {
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        eadk::push_rect_uniform(rect, color);
+    }
+
+    fn draw_line(&mut self, x0: u16, y0: u16, x1: u16, y1: u16, color: Color) {
+        display::draw_line(x0, y0, x1, y1, color);
+    }
+
+    fn draw_string(&mut self, text: &str, x: u16, y: u16, color: Color, background: Color) {
+        display::draw_string(text, x, y, false, color, background);
+    }
+}
+
+impl Canvas for Buffer {
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        Buffer::fill_rect(self, rect, color);
+    }
+
+    fn draw_line(&mut self, x0: u16, y0: u16, x1: u16, y1: u16, color: Color) {
+        Buffer::draw_line(self, x0, y0, x1, y1, color);
+    }
+
+    fn draw_string(&mut self, text: &str, x: u16, y: u16, color: Color, background: Color) {
+        Buffer::draw_string(self, text, x, y, false, color, background);
+    }
+}
+
+/// A named list of values, drawn in one color.
+pub struct DataSet {
+    pub label: String,
+    pub values: Vec<f32>,
+    pub color: Color,
+}
+
+impl DataSet {
+    pub fn new(label: &str, values: &[f32], color: Color) -> Self {
+        Self {
+            label: String::from(label),
+            values: values.to_vec(),
+            color,
+        }
+    }
+}
+
+/// Bars grouped by category, one bar per data set in each group.
+///
+/// ```ignore
+/// BarChart::new(&["Mon", "Tue", "Wed"])
+///     .with_title("Visitors")
+///     .with_data_set(DataSet::new("2024", &[12.0, 18.0, 9.0], PALETTE[0]))
+///     .with_data_set(DataSet::new("2025", &[15.0, 21.0, 11.0], PALETTE[1]))
+///     .draw(&mut Screen, Rect::SCREEN);
+/// ```
+pub struct BarChart {
+    title: String,
+    categories: Vec<String>,
+    data_sets: Vec<DataSet>,
+}
+
+impl BarChart {
+    pub fn new(categories: &[&str]) -> Self {
+        Self {
+            title: String::new(),
+            categories: categories
+                .iter()
+                .map(|&category| String::from(category))
+                .collect(),
+            data_sets: Vec::new(),
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = String::from(title);
+        self
+    }
+
+    pub fn with_data_set(mut self, data_set: DataSet) -> Self {
+        self.data_sets.push(data_set);
+        self
+    }
+
+    pub fn draw(&self, canvas: &mut impl Canvas, rect: Rect) {
+        let values = self
+            .data_sets
+            .iter()
+            .flat_map(|set| set.values.iter().copied());
+        let legend = legend_of(&self.data_sets);
+        let frame = Frame::draw(canvas, rect, &self.title, &legend, value_range(values));
+        let count = self.categories.len().max(1) as u16;
+        let group_width = frame.area.width / count;
+        let bar_width = group_width.saturating_sub(2 * MARGIN) / self.data_sets.len().max(1) as u16;
+
+        for (index, category) in self.categories.iter().enumerate() {
+            let group_x = frame.area.x + index as u16 * group_width;
+            for (set_index, set) in self.data_sets.iter().enumerate() {
+                let Some(&value) = set.values.get(index) else {
+                    continue;
+                };
+                let x = group_x + MARGIN + set_index as u16 * bar_width;
+                frame.bar(canvas, x, bar_width.max(1), value, set.color);
+            }
+            frame.category_label(canvas, category, group_x, group_width);
+        }
+        frame.draw_axes(canvas);
+    }
+}
+
+/// Values joined by lines, one line per data set, over evenly spaced categories.
+pub struct LineChart {
+    title: String,
+    categories: Vec<String>,
+    data_sets: Vec<DataSet>,
+}
+
+impl LineChart {
+    pub fn new(categories: &[&str]) -> Self {
+        Self {
+            title: String::new(),
+            categories: categories
+                .iter()
+                .map(|&category| String::from(category))
+                .collect(),
+            data_sets: Vec::new(),
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = String::from(title);
+        self
+    }
+
+    pub fn with_data_set(mut self, data_set: DataSet) -> Self {
+        self.data_sets.push(data_set);
+        self
+    }
+
+    pub fn draw(&self, canvas: &mut impl Canvas, rect: Rect) {
+        let values = self
+            .data_sets
+            .iter()
+            .flat_map(|set| set.values.iter().copied());
+        let legend = legend_of(&self.data_sets);
+        let frame = Frame::draw(canvas, rect, &self.title, &legend, value_range(values));
+        let count = self.categories.len().max(1) as u16;
+        let column_width = frame.area.width / count;
+        // The points are at the center of the columns.
+        let x_of = |index: usize| frame.area.x + index as u16 * column_width + column_width / 2;
+
+        for (index, category) in self.categories.iter().enumerate() {
+            let x = frame.area.x + index as u16 * column_width;
+            frame.category_label(canvas, category, x, column_width);
+        }
+        frame.draw_axes(canvas);
+        for set in &self.data_sets {
+            let points: Vec<(u16, u16)> = set
+                .values
+                .iter()
+                .take(self.categories.len())
+                .enumerate()
+                .map(|(index, &value)| (x_of(index), frame.y_of(value)))
+                .collect();
+            for pair in points.windows(2) {
+                canvas.draw_line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, set.color);
+            }
+            for &(x, y) in &points {
+                let half = MARKER_SIZE / 2;
+                let marker =
+                    Rect::new_square(x.saturating_sub(half), y.saturating_sub(half), MARKER_SIZE);
+                canvas.fill_rect(marker, set.color);
+            }
+        }
+    }
+}
+
+/// The distribution of values, counted in bins of equal width.
+pub struct Histogram {
+    title: String,
+    values: Vec<f32>,
+    bins: Option<usize>,
+    color: Color,
+}
+
+impl Histogram {
+    /// Counts the values in a number of bins chosen from the number of values
+    /// (Sturges' rule). Non finite values are ignored.
+    pub fn new(values: &[f32], color: Color) -> Self {
+        Self {
+            title: String::new(),
+            values: values
+                .iter()
+                .copied()
+                .filter(|value| value.is_finite())
+                .collect(),
+            bins: None,
+            color,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = String::from(title);
+        self
+    }
+
+    /// Uses a fixed number of bins (at least 1). Only as many bins as the chart
+    /// is wide in pixels are drawn.
+    pub fn with_bins(mut self, bins: usize) -> Self {
+        self.bins = Some(bins.max(1));
+        self
+    }
+
+    /// The number of bins the values are counted in.
+    pub fn bin_count(&self) -> usize {
+        self.bins.unwrap_or_else(|| {
+            // Sturges' rule: log2(n) + 1, rounded up.
+            let n = self.values.len().max(1);
+            (usize::BITS - (n - 1).leading_zeros()) as usize + 1
+        })
+    }
+
+    /// The lowest value and the width of the bins.
+    fn bin_range(&self) -> (f32, f32) {
+        let low = self.values.iter().copied().fold(f32::INFINITY, f32::min);
+        let high = self
+            .values
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max);
+        if low > high {
+            return (0.0, 1.0);
+        }
+        let width = (high - low) / self.bin_count() as f32;
+        (low, if width > 0.0 { width } else { 1.0 })
+    }
+
+    /// The number of values in each bin, the last bin including the highest value.
+    pub fn counts(&self) -> Vec<u32> {
+        let bins = self.bin_count();
+        let (low, width) = self.bin_range();
+        let mut counts = alloc::vec![0; bins];
+        for &value in &self.values {
+            let bin = (((value - low) / width) as usize).min(bins - 1);
+            counts[bin] += 1;
+        }
+        counts
+    }
+
+    pub fn draw(&self, canvas: &mut impl Canvas, rect: Rect) {
+        let counts = self.counts();
+        let highest = counts.iter().copied().max().unwrap_or(0) as f32;
+        let frame = Frame::draw(canvas, rect, &self.title, &[], (0.0, highest.max(1.0)));
+        let area_width = frame.area.width as usize;
+        let bar_width = (area_width / counts.len()).max(1);
+        // With more bins than pixels, the bins past the right of the area are left out.
+        let bins = counts.len().min(area_width / bar_width) as u16;
+        let bar_width = bar_width as u16;
+        let outline = self.color.lerp(Color::BLACK, 0.3);
+
+        for (index, &count) in counts.iter().take(bins as usize).enumerate() {
+            let x = frame.area.x + index as u16 * bar_width;
+            frame.bar(canvas, x, bar_width, count as f32, self.color);
+            if count > 0 {
+                let top = frame.y_of(count as f32);
+                canvas.draw_line(x, top, x, frame.baseline(), outline);
+            }
+        }
+
+        // Label the edges of the bins, skipping some when they are too close.
+        let (low, width) = self.bin_range();
+        let label_width = |edge: u16| {
+            let text = format_number(low + edge as f32 * width, width);
+            text.len() as u16 * CHAR_WIDTH + MARGIN
+        };
+        let widest = (0..=bins).map(label_width).max().unwrap_or(0);
+        let every = widest.div_ceil(bar_width).max(1);
+        for edge in (0..=bins).step_by(every as usize) {
+            let text = format_number(low + edge as f32 * width, width);
+            let center = frame.area.x + edge * bar_width;
+            frame.centered_label(canvas, &text, center);
+        }
+        frame.draw_axes(canvas);
+    }
+}
+
+/// Shares of a whole, with their percentages in the legend.
+pub struct PieChart {
+    title: String,
+    slices: Vec<(String, f32, Color)>,
+}
+
+impl PieChart {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            slices: Vec::new(),
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = String::from(title);
+        self
+    }
+
+    /// Adds a slice. Negative and non finite values count as 0.
+    pub fn with_slice(mut self, label: &str, value: f32, color: Color) -> Self {
+        let value = if value.is_finite() {
+            value.max(0.0)
+        } else {
+            0.0
+        };
+        self.slices.push((String::from(label), value, color));
+        self
+    }
+
+    /// Draws the pie on the left of the rect and the legend on the right,
+    /// starting at the top and going clockwise.
+    pub fn draw(&self, canvas: &mut impl Canvas, rect: Rect) {
+        let theme = theme::current();
+        canvas.fill_rect(rect, theme.background);
+        let mut area = rect;
+        if !self.title.is_empty() {
+            draw_title(canvas, rect, &self.title);
+            area = below(area, CHAR_HEIGHT + MARGIN);
+        }
+
+        let total: f32 = self.slices.iter().map(|slice| slice.1).sum();
+        let percent = |value: f32| {
+            if total > 0.0 {
+                value / total * 100.0
+            } else {
+                0.0
+            }
+        };
+        let legend: Vec<String> = self
+            .slices
+            .iter()
+            .map(|(label, value, _)| format!("{} {}%", label, libm::roundf(percent(*value))))
+            .collect();
+        let legend_width = legend
+            .iter()
+            .map(|text| text.len() as u16)
+            .max()
+            .unwrap_or(0)
+            * CHAR_WIDTH
+            + SWATCH_SIZE
+            + 3 * MARGIN;
+
+        // The legend, on the right and vertically centered.
+        let legend_x = area.x + area.width.saturating_sub(legend_width) + MARGIN;
+        let legend_height = legend.len() as u16 * CHAR_HEIGHT;
+        let mut y = area.y + area.height.saturating_sub(legend_height) / 2;
+        for (text, (_, _, color)) in legend.iter().zip(&self.slices) {
+            let swatch_y = y + (CHAR_HEIGHT - SWATCH_SIZE) / 2;
+            canvas.fill_rect(Rect::new_square(legend_x, swatch_y, SWATCH_SIZE), *color);
+            canvas.draw_string(
+                text,
+                legend_x + SWATCH_SIZE + MARGIN,
+                y,
+                theme.secondary_text,
+                theme.background,
+            );
+            y += CHAR_HEIGHT;
+        }
+
+        // The pie, filled row by row with one rect per run of the same slice.
+        let pie_width = area.width.saturating_sub(legend_width);
+        let radius = (pie_width.min(area.height) / 2).saturating_sub(MARGIN) as f32;
+        if total <= 0.0 || radius < 1.0 {
+            return;
+        }
+        let center_x = area.x as f32 + pie_width as f32 / 2.0;
+        let center_y = area.y as f32 + area.height as f32 / 2.0;
+        let mut ends = Vec::with_capacity(self.slices.len());
+        let mut sum = 0.0;
+        for slice in &self.slices {
+            sum += slice.1;
+            ends.push(sum / total);
+        }
+        let slice_at = |x: f32, y: f32| {
+            let (dx, dy) = (x - center_x, y - center_y);
+            if dx * dx + dy * dy > radius * radius {
+                return None;
+            }
+            // From 0 at the top to 1, clockwise.
+            let turn = libm::atan2f(dx, -dy) / TAU;
+            let turn = if turn < 0.0 { turn + 1.0 } else { turn };
+            Some(
+                ends.iter()
+                    .position(|&end| turn < end)
+                    .unwrap_or(ends.len() - 1),
+            )
+        };
+
+        let top = libm::floorf(center_y - radius) as u16;
+        let bottom = libm::ceilf(center_y + radius) as u16;
+        let left = libm::floorf(center_x - radius) as u16;
+        let right = libm::ceilf(center_x + radius) as u16;
+        for y in top..bottom {
+            let mut run_start = left;
+            let mut run_slice = slice_at(left as f32 + 0.5, y as f32 + 0.5);
+            for x in left + 1..=right {
+                let slice = if x < right {
+                    slice_at(x as f32 + 0.5, y as f32 + 0.5)
+                } else {
+                    None
+                };
+                if slice != run_slice || x == right {
+                    if let Some(index) = run_slice {
+                        canvas.fill_rect(
+                            Rect::new(run_start, y, x - run_start, 1),
+                            self.slices[index].2,
+                        );
+                    }
+                    run_start = x;
+                    run_slice = slice;
+                }
+            }
+        }
+    }
+}
+
+impl Default for PieChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn legend_of(data_sets: &[DataSet]) -> Vec<(&str, Color)> {
+    data_sets
+        .iter()
+        .filter(|set| !set.label.is_empty())
+        .map(|set| (set.label.as_str(), set.color))
+        .collect()
+}
+
+/// The range of the value axis: it always includes 0, the base of the bars.
+fn value_range(values: impl Iterator<Item = f32>) -> (f32, f32) {
+    let (low, high) = values
+        .filter(|value| value.is_finite())
+        .fold((0.0f32, 0.0f32), |(low, high), value| {
+            (low.min(value), high.max(value))
+        });
+    if high > low { (low, high) } else { (0.0, 1.0) }
+}
+
+/// The rect without its first `height` rows.
+fn below(rect: Rect, height: u16) -> Rect {
+    let height = height.min(rect.height);
+    Rect::new(rect.x, rect.y + height, rect.width, rect.height - height)
+}
+
+fn draw_title(canvas: &mut impl Canvas, rect: Rect, title: &str) {
+    let theme = theme::current();
+    let width = title.chars().count() as u16 * CHAR_WIDTH;
+    let x = rect.x + rect.width.saturating_sub(width) / 2;
+    canvas.draw_string(title, x, rect.y, theme.text, theme.background);
+}
+
+/// The axes, ticks and texts around the data of a bar, line or histogram chart.
+struct Frame {
+    /// Where the data is drawn.
+    area: Rect,
+    low: f32,
+    high: f32,
+}
+
+impl Frame {
+    /// Clears the rect, draws the title, the legend and the value labels and grid,
+    /// and returns the frame with the space left for the data.
+    fn draw(
+        canvas: &mut impl Canvas,
+        rect: Rect,
+        title: &str,
+        legend: &[(&str, Color)],
+        (low, high): (f32, f32),
+    ) -> Self {
+        let theme = theme::current();
+        canvas.fill_rect(rect, theme.background);
+        let mut area = rect;
+        if !title.is_empty() {
+            draw_title(canvas, rect, title);
+            area = below(area, CHAR_HEIGHT + MARGIN);
+        }
+
+        // The legend on the last row.
+        if !legend.is_empty() {
+            area.height = area.height.saturating_sub(CHAR_HEIGHT + MARGIN);
+            let mut x = area.x + MARGIN;
+            let y = area.y + area.height + MARGIN;
+            for &(label, color) in legend {
+                let swatch_y = y + (CHAR_HEIGHT - SWATCH_SIZE) / 2;
+                canvas.fill_rect(Rect::new_square(x, swatch_y, SWATCH_SIZE), color);
+                x += SWATCH_SIZE + MARGIN;
+                canvas.draw_string(label, x, y, theme.secondary_text, theme.background);
+                x += label.chars().count() as u16 * CHAR_WIDTH + 2 * MARGIN;
+            }
+        }
+
+        // Room for the category labels under the data.
+        area.height = area.height.saturating_sub(CHAR_HEIGHT + MARGIN);
+        // Round the range to whole ticks.
+        let step = tick_step(high - low, TARGET_TICKS);
+        let low = libm::floorf(low / step) * step;
+        let high = libm::ceilf(high / step) * step;
+        // Values too far apart for a float have no ticks.
+        let labels: Vec<(f32, String)> = if (high - low).is_finite() {
+            ticks(low, high, step)
+                .map(|value| (value, format_number(value, step)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let label_width = labels
+            .iter()
+            .map(|(_, text)| text.len() as u16)
+            .max()
+            .unwrap_or(0)
+            * CHAR_WIDTH;
+        let offset = (label_width + MARGIN).min(area.width);
+        area.x += offset;
+        area.width = area.width.saturating_sub(offset + MARGIN);
+        // Leave room for half a label above the highest tick.
+        area = below(area, CHAR_HEIGHT / 2);
+
+        let frame = Self { area, low, high };
+        for (value, text) in labels {
+            let y = frame.y_of(value);
+            canvas.fill_rect(Rect::new(area.x, y, area.width, 1), theme.separator);
+            let text_x = area
+                .x
+                .saturating_sub(text.len() as u16 * CHAR_WIDTH + MARGIN);
+            let text_y = y.saturating_sub(CHAR_HEIGHT / 2);
+            canvas.draw_string(
+                &text,
+                text_x,
+                text_y,
+                theme.secondary_text,
+                theme.background,
+            );
+        }
+        frame
+    }
+
+    /// The vertical position of a value.
+    fn y_of(&self, value: f32) -> u16 {
+        let span = self.high - self.low;
+        let t = if span > 0.0 {
+            (value - self.low) / span
+        } else {
+            0.0
+        };
+        let bottom = self.area.y + self.area.height.saturating_sub(1);
+        bottom - libm::roundf(t.clamp(0.0, 1.0) * self.area.height.saturating_sub(1) as f32) as u16
+    }
+
+    /// The vertical position of 0, where the bars start.
+    fn baseline(&self) -> u16 {
+        self.y_of(0.0)
+    }
+
+    /// Draws a bar from 0 to the value, going down for negative values.
+    fn bar(&self, canvas: &mut impl Canvas, x: u16, width: u16, value: f32, color: Color) {
+        let (base, top) = (self.baseline(), self.y_of(value));
+        let rect = Rect::new(x, base.min(top), width, base.abs_diff(top) + 1);
+        canvas.fill_rect(rect, color);
+    }
+
+    /// Draws the label of a category under the data, cut to fit its width.
+    fn category_label(&self, canvas: &mut impl Canvas, label: &str, x: u16, width: u16) {
+        let theme = theme::current();
+        let fitting = (width / CHAR_WIDTH) as usize;
+        let text: String = label.chars().take(fitting).collect();
+        let text_width = text.chars().count() as u16 * CHAR_WIDTH;
+        let y = self.area.y + self.area.height + MARGIN;
+        canvas.draw_string(
+            &text,
+            x + (width - text_width) / 2,
+            y,
+            theme.secondary_text,
+            theme.background,
+        );
+    }
+
+    /// Draws a label under the data, centered on `x` but kept inside the chart.
+    fn centered_label(&self, canvas: &mut impl Canvas, text: &str, x: u16) {
+        let theme = theme::current();
+        let width = text.len() as u16 * CHAR_WIDTH;
+        let left = x.saturating_sub(width / 2);
+        let y = self.area.y + self.area.height + MARGIN;
+        canvas.draw_string(text, left, y, theme.secondary_text, theme.background);
+    }
+
+    /// Draws the value axis on the left and the base line at 0.
+    fn draw_axes(&self, canvas: &mut impl Canvas) {
+        let theme = theme::current();
+        let area = self.area;
+        canvas.fill_rect(Rect::new(area.x, area.y, 1, area.height), theme.text);
+        canvas.fill_rect(
+            Rect::new(area.x, self.baseline(), area.width, 1),
+            theme.text,
+        );
+    }
+}
@@ -0,0 +1,374 @@
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+
+use crate::display::{self, Color, Rect, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::keyboard::{self, Key};
+use crate::theme;
+use crate::time::{Duration, Instant};
+use crate::ui::{self, Button, ROW_HEIGHT, TITLE_BAR_HEIGHT, Widget};
+
+/// The widest a dialog or a toast can be, in pixels.
+const MAX_WIDTH: u16 = SCREEN_WIDTH - 40;
+/// The narrowest a button of a dialog can be, in pixels.
+const MIN_BUTTON_WIDTH: u16 = 60;
+/// The space between the bottom of the screen and a toast, in pixels.
+const TOAST_MARGIN: u16 = 16;
+/// How long a toast stays on the screen by default.
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+/// The pixels of a part of the screen, to put them back after drawing over them.
+///
+/// The pixels are kept on the heap, `width * height * 2` bytes.
+pub struct SavedRegion {
+    rect: Rect,
+    pixels: Vec<Color>,
+}
+
+impl SavedRegion {
+    /// Reads the pixels of the rect.
+    pub fn save(rect: Rect) -> Self {
+        Self {
+            rect,
+            pixels: display::get_rect(rect),
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Draws the saved pixels back.
+    pub fn restore(&self) {
+        display::eadk::push_rect(self.rect, &self.pixels);
+    }
+}
+
+/// Cuts a text into lines of at most `max_chars` chars, at spaces when possible
+/// and at every `\n`.
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut len = 0;
+        for word in paragraph.split(' ') {
+            let word_len = word.chars().count();
+            if len > 0 && len + 1 + word_len > max_chars {
+                lines.push(core::mem::take(&mut line));
+                len = 0;
+            }
+            if len > 0 {
+                line.push(' ');
+                len += 1;
+            }
+            // Words longer than a line are cut.
+            for c in word.chars() {
+                if len == max_chars {
+                    lines.push(core::mem::take(&mut line));
+                    len = 0;
+                }
+                line.push(c);
+                len += 1;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// A rect of the given size, centered on the screen.
+fn centered(width: u16, height: u16) -> Rect {
+    let width = width.min(SCREEN_WIDTH);
+    let height = height.min(SCREEN_HEIGHT);
+    Rect::new(
+        (SCREEN_WIDTH - width) / 2,
+        (SCREEN_HEIGHT - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// A modal box with a message and buttons, drawn over the app.
+///
+/// The pixels under the dialog are saved before it is drawn and put back when
+/// it is closed, so the app doesn't have to redraw anything. They take
+/// `width * height * 2` bytes of heap while the dialog is open.
+/// Messages too long for the screen are cut, and buttons too wide for the
+/// dialog are narrowed, their labels cut.
+/// `Left` and `Right` choose a button, `Ok` or `Exe` press it and `Back` closes
+/// the dialog without choosing.
+///
+/// ```ignore
+/// let choice = Dialog::new("Save the changes?")
+///     .with_title("Quit")
+///     .with_buttons(&["Discard", "Cancel", "Save"])
+///     .with_selected(2)
+///     .run();
+/// ```
+pub struct Dialog {
+    title: String,
+    message: String,
+    buttons: Vec<String>,
+    selected: usize,
+}
+
+impl Dialog {
+    /// Creates a dialog with a single "OK" button.
+    pub fn new(message: &str) -> Self {
+        Self {
+            title: String::new(),
+            message: String::from(message),
+            buttons: Vec::from([String::from("OK")]),
+            selected: 0,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = String::from(title);
+        self
+    }
+
+    /// Replaces the buttons, from left to right.
+    pub fn with_buttons(mut self, buttons: &[&str]) -> Self {
+        self.buttons = buttons.iter().map(|&button| String::from(button)).collect();
+        self.selected = self.selected.min(self.buttons.len().saturating_sub(1));
+        self
+    }
+
+    /// Selects a button when the dialog opens, the first one by default.
+    pub fn with_selected(mut self, selected: usize) -> Self {
+        self.selected = selected.min(self.buttons.len().saturating_sub(1));
+        self
+    }
+
+    /// Shows the dialog until a button is pressed, returns its index,
+    /// or `None` if the user pressed `Back`.
+    ///
+    /// Allocates `width * height * 2` bytes of heap to save the pixels under
+    /// the dialog.
+    pub fn run(&mut self) -> Option<usize> {
+        let (rect, lines, buttons) = self.layout();
+        let saved = SavedRegion::save(rect);
+        self.draw_frame(rect, &lines);
+
+        let choice = loop {
+            for (index, button) in buttons.iter().enumerate() {
+                button.draw(index == self.selected);
+            }
+            match keyboard::wait_for_key() {
+                Key::Left => self.selected = self.selected.saturating_sub(1),
+                Key::Right => {
+                    self.selected = (self.selected + 1).min(buttons.len().saturating_sub(1));
+                }
+                Key::Ok | Key::Exe if !buttons.is_empty() => break Some(self.selected),
+                Key::Back => break None,
+                _ => (),
+            }
+        };
+        saved.restore();
+        choice
+    }
+
+    /// The rect of the dialog, the lines of the message and the buttons.
+    fn layout(&self) -> (Rect, Vec<String>, Vec<Button>) {
+        let theme = theme::current();
+        let (char_width, char_height) = theme.char_size();
+        let max_chars = ((MAX_WIDTH - 2 * theme.padding) / char_width) as usize;
+        let mut lines = wrap(&self.message, max_chars);
+        let title_height = if self.title.is_empty() {
+            0
+        } else {
+            TITLE_BAR_HEIGHT
+        };
+        let chrome_height = title_height + 3 * theme.padding + ROW_HEIGHT;
+        let max_lines = SCREEN_HEIGHT.saturating_sub(chrome_height) / char_height;
+        lines.truncate(max_lines as usize);
+        let text_width = lines
+            .iter()
+            .map(|line| ui::text_width(line))
+            .max()
+            .unwrap_or(0);
+        let mut button_widths: Vec<u16> = self
+            .buttons
+            .iter()
+            .map(|label| (ui::text_width(label) + 2 * theme.padding).max(MIN_BUTTON_WIDTH))
+            .collect();
+        let count = self.buttons.len() as u16;
+        let gaps = count.saturating_sub(1);
+        let mut spacing = theme.padding;
+        let mut buttons_width = button_widths
+            .iter()
+            .fold(spacing.saturating_mul(gaps), |sum, &width| {
+                sum.saturating_add(width)
+            });
+        let inner_width = MAX_WIDTH - 2 * theme.padding;
+        if buttons_width > inner_width {
+            // The buttons share the width equally, without space between them
+            // if there are too many.
+            if spacing.saturating_mul(gaps) >= inner_width {
+                spacing = 0;
+            }
+            let button_width = (inner_width - spacing * gaps) / count;
+            button_widths.fill(button_width);
+            buttons_width = button_width * count + spacing * gaps;
+        }
+        let title_width = ui::text_width(&self.title);
+        let width =
+            (text_width.max(buttons_width).max(title_width) + 2 * theme.padding).min(MAX_WIDTH);
+
+        let height = chrome_height + lines.len() as u16 * char_height;
+        let rect = centered(width, height);
+
+        // The buttons are centered on the last row.
+        let mut x = rect.x + width.saturating_sub(buttons_width) / 2;
+        let y = rect.y + rect.height.saturating_sub(ROW_HEIGHT + theme.padding);
+        let buttons = self
+            .buttons
+            .iter()
+            .zip(button_widths)
+            .map(|(label, button_width)| {
+                let button = Button::new(Rect::new(x, y, button_width, ROW_HEIGHT), label);
+                x += button_width + spacing;
+                button
+            })
+            .collect();
+        (rect, lines, buttons)
+    }
+
+    fn draw_frame(&self, rect: Rect, lines: &[String]) {
+        let theme = theme::current();
+        let char_height = theme.char_size().1;
+        display::eadk::push_rect_uniform(rect, theme.background);
+        let mut y = rect.y + theme.padding;
+        if !self.title.is_empty() {
+            let bar = Rect::new(rect.x, rect.y, rect.width, TITLE_BAR_HEIGHT);
+            display::eadk::push_rect_uniform(bar, theme.accent);
+            let x = rect.x + rect.width.saturating_sub(ui::text_width(&self.title)) / 2;
+            ui::draw_text(&self.title, bar, x, theme.on_accent, theme.accent);
+            y += TITLE_BAR_HEIGHT;
+        }
+        for line in lines {
+            let x = rect.x + rect.width.saturating_sub(ui::text_width(line)) / 2;
+            display::draw_string(line, x, y, theme.large_font, theme.text, theme.background);
+            y += char_height;
+        }
+        ui::draw_border(rect, theme.secondary_text);
+    }
+}
+
+/// Asks a yes or no question, returns true if the user chose "OK".
+///
+/// "OK" is selected by default, `Back` counts as "Cancel".
+pub fn confirm(message: &str) -> bool {
+    Dialog::new(message)
+        .with_buttons(&["Cancel", "OK"])
+        .with_selected(1)
+        .run()
+        == Some(1)
+}
+
+/// Shows a message until the user presses `Ok`, `Exe` or `Back`.
+pub fn alert(message: &str) {
+    Dialog::new(message).run();
+}
+
+/// A short message at the bottom of the screen, going away by itself.
+///
+/// Showing the toast doesn't block: call [`Toast::update`] in the main loop to
+/// remove it once its time is up. The pixels under the toast are put back when
+/// it goes away, so the app should not draw there while it is shown.
+///
+/// ```ignore
+/// let mut toast = Toast::new("Saved");
+/// toast.show();
+/// loop {
+///     toast.update();
+///     // ...
+/// }
+/// ```
+pub struct Toast {
+    message: String,
+    duration: Duration,
+    shown: Option<(SavedRegion, Instant)>,
+}
+
+impl Toast {
+    /// Creates a toast staying 2 seconds on the screen.
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: String::from(message),
+            duration: TOAST_DURATION,
+            shown: None,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.shown.is_some()
+    }
+
+    /// Draws the toast, and restarts its timer if it was already shown.
+    ///
+    /// Allocates `width * height * 2` bytes of heap to save the pixels under
+    /// the toast, about 4 KB for a short message.
+    pub fn show(&mut self) {
+        let deadline = Instant::now() + self.duration;
+        if let Some((_, until)) = &mut self.shown {
+            *until = deadline;
+            return;
+        }
+
+        let theme = theme::current();
+        let (char_width, char_height) = theme.char_size();
+        let max_chars = ((MAX_WIDTH - 2 * theme.padding) / char_width) as usize;
+        let text: String = self.message.chars().take(max_chars).collect();
+        let width = ui::text_width(&text) + 2 * theme.padding;
+        let height = char_height + theme.padding;
+        let rect = Rect::new(
+            (SCREEN_WIDTH - width) / 2,
+            SCREEN_HEIGHT - height - TOAST_MARGIN,
+            width,
+            height,
+        );
+        let saved = SavedRegion::save(rect);
+        // The colors of the theme swapped, to stand out over the app.
+        display::eadk::push_rect_uniform(rect, theme.text);
+        ui::draw_text(
+            &text,
+            rect,
+            rect.x + theme.padding,
+            theme.background,
+            theme.text,
+        );
+        self.shown = Some((saved, deadline));
+    }
+
+    /// Removes the toast if its time is up, returns whether it is still visible.
+    pub fn update(&mut self) -> bool {
+        if let Some((_, until)) = &self.shown
+            && Instant::now() >= *until
+        {
+            self.dismiss();
+        }
+        self.is_visible()
+    }
+
+    /// Removes the toast now.
+    pub fn dismiss(&mut self) {
+        if let Some((saved, _)) = self.shown.take() {
+            saved.restore();
+        }
+    }
+}
+
+impl Drop for Toast {
+    /// A toast doesn't stay on the screen once it is gone.
+    fn drop(&mut self) {
+        self.dismiss();
+    }
+}
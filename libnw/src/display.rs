@@ -2,6 +2,8 @@ extern crate alloc;
 
 use alloc::{borrow::Cow, string::String, vec::Vec};
 
+pub use eadk::wait_for_vblank;
+
 /// The width of the screen in pixels.
 pub const SCREEN_WIDTH: u16 = 320;
 /// The height of the screen in pixels.
@@ -87,12 +89,12 @@ impl Rect {
 
 /// An RGB 5-6-5 color: 5 bits for red, 6 bits for green and 5 bits for blue.
 #[repr(transparent)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Color(u16);
 
 impl Color {
     /// Creates a color directly from a u16.
-    pub fn new(rgb565: u16) -> Self {
+    pub const fn new(rgb565: u16) -> Self {
         Self(rgb565)
     }
 
@@ -100,7 +102,7 @@ impl Color {
     ///
     /// Each channel must be between 0 and 255, and is then converted
     /// so the 3 channels can fit in a u16 (some precision is lost).
-    pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
         let r = (r & 0b11111000) as u16;
         let g = (g & 0b11111100) as u16;
         let b = b as u16;
@@ -118,6 +120,20 @@ impl Color {
         (r, g, b)
     }
 
+    /// The raw RGB 5-6-5 value of the color.
+    pub const fn to_rgb565(&self) -> u16 {
+        self.0
+    }
+
+    /// Mixes two colors, `t` going from 0 (only `self`) to 1 (only `other`).
+    pub fn lerp(&self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t + 0.5) as u8;
+        let (r1, g1, b1) = self.separate_channels();
+        let (r2, g2, b2) = other.separate_channels();
+        Color::from_rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+    }
+
     // Basic colors
     /// Pure black (0, 0, 0)
     pub const BLACK: Self = Self(0x0);
@@ -131,6 +147,137 @@ impl Color {
     pub const BLUE: Self = Self(0x1F);
 }
 
+/// An off-screen image, drawn on the screen in one go with [`Buffer::push`].
+///
+/// Shapes drawn in a buffer don't flicker, but it takes 2 bytes of heap per pixel.
+/// Text is the exception: [`Buffer::draw_string`] briefly draws on the screen.
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    pixels: Vec<Color>,
+}
+
+impl Buffer {
+    /// Creates a buffer filled with the given color.
+    pub fn new(width: u16, height: u16, color: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: alloc::vec![color; width as usize * height as usize],
+        }
+    }
+
+    /// The width of the buffer in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// The height of the buffer in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The pixels, from left to right then top to bottom.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// The pixels, from left to right then top to bottom.
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    /// Returns the color of a pixel, or `None` if it is outside the buffer.
+    pub fn get_pixel(&self, x: u16, y: u16) -> Option<Color> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    /// Sets the color of a pixel, does nothing if it is outside the buffer.
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: Color) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = color;
+        }
+    }
+
+    /// Fills the whole buffer with the given color.
+    pub fn fill(&mut self, color: Color) {
+        self.pixels.fill(color);
+    }
+
+    /// Fills a rect of the buffer, the part outside the buffer is ignored.
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let x_end = (rect.x as usize + rect.width as usize).min(self.width as usize);
+        let y_end = (rect.y as usize + rect.height as usize).min(self.height as usize);
+        for y in rect.y as usize..y_end {
+            let row = y * self.width as usize;
+            let start = (row + rect.x as usize).min(row + x_end);
+            self.pixels[start..row + x_end].fill(color);
+        }
+    }
+
+    /// Draws a one pixel wide line, the part outside the buffer is ignored.
+    pub fn draw_line(&mut self, x0: u16, y0: u16, x1: u16, y1: u16, color: Color) {
+        line_points(x0, y0, x1, y1, |x, y| self.set_pixel(x, y, color));
+    }
+
+    /// Draws a string in the buffer, the part outside the buffer is ignored.
+    ///
+    /// `eadk` can only draw text on the screen, so the string is drawn in the top left
+    /// corner of the screen, copied into the buffer, then the screen is restored.
+    pub fn draw_string(
+        &mut self,
+        string: &str,
+        x: u16,
+        y: u16,
+        large_font: bool,
+        text_color: Color,
+        background_color: Color,
+    ) {
+        let (char_width, char_height) = if large_font {
+            (LARGE_CHAR_WIDTH, LARGE_CHAR_HEIGHT)
+        } else {
+            (CHAR_WIDTH, CHAR_HEIGHT)
+        };
+        let chars = string.trim_end_matches('\0').chars().count() as u16;
+        let scratch = Rect::new(
+            0,
+            0,
+            chars.saturating_mul(char_width).min(SCREEN_WIDTH),
+            char_height,
+        );
+        let width = scratch.width.min(self.width.saturating_sub(x));
+        let height = scratch.height.min(self.height.saturating_sub(y));
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let saved = get_rect(scratch);
+        draw_string(string, 0, 0, large_font, text_color, background_color);
+        let text = get_rect(scratch);
+        eadk::push_rect(scratch, &saved);
+
+        for row in 0..height as usize {
+            let source = row * scratch.width as usize;
+            let target = (y as usize + row) * self.width as usize + x as usize;
+            self.pixels[target..target + width as usize]
+                .copy_from_slice(&text[source..source + width as usize]);
+        }
+    }
+
+    /// Draws the buffer on the screen, its top left corner at (x, y).
+    pub fn push(&self, x: u16, y: u16) {
+        eadk::push_rect(Rect::new(x, y, self.width, self.height), &self.pixels);
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+}
+
 /// Returns the pixels' color in the given rect.
 ///
 /// The screen is read from left to right then top to bottom.
@@ -158,6 +305,49 @@ pub fn clear(color: Color) {
     eadk::push_rect_uniform(Rect::SCREEN, color);
 }
 
+/// Draws a one pixel wide line between two points, both ends included.
+pub fn draw_line(x0: u16, y0: u16, x1: u16, y1: u16, color: Color) {
+    // Horizontal and vertical lines are a single rect.
+    if x0 == x1 || y0 == y1 {
+        let rect = Rect::new(
+            x0.min(x1),
+            y0.min(y1),
+            x0.abs_diff(x1) + 1,
+            y0.abs_diff(y1) + 1,
+        );
+        eadk::push_rect_uniform(rect, color);
+        return;
+    }
+    line_points(x0, y0, x1, y1, |x, y| set_pixel(x, y, color));
+}
+
+/// Calls `plot` for every pixel of a line between two points, both ends included.
+fn line_points(x0: u16, y0: u16, x1: u16, y1: u16, mut plot: impl FnMut(u16, u16)) {
+    // Bresenham
+    let (x1, y1) = (x1 as i32, y1 as i32);
+    let (mut x, mut y) = (x0 as i32, y0 as i32);
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let step_x = if x < x1 { 1 } else { -1 };
+    let step_y = if y < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    loop {
+        plot(x as u16, y as u16);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
 /// Draws a string, ensuring it ends correctly
 pub fn draw_string(
     string: &str,
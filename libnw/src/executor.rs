@@ -0,0 +1,336 @@
+extern crate alloc;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use crate::keyboard::{self, Key};
+use crate::time::{self, Duration, Instant};
+
+/// What the pending futures are waiting for, gathered while polling them.
+///
+/// The futures of this module don't use wakers: the executor polls every task
+/// after each event, and only needs to know which event to wait for.
+struct Reactor {
+    /// The earliest deadline of the pending [`Sleep`]s.
+    deadline: Option<Instant>,
+    /// Whether a [`NextKey`] is pending.
+    wants_key: bool,
+    /// Whether a task yielded and must be polled again right away.
+    yielded: bool,
+    /// The key read during the last wait, not yet taken by a [`NextKey`].
+    key: Option<Key>,
+}
+
+static mut REACTOR: Reactor = Reactor {
+    deadline: None,
+    wants_key: false,
+    yielded: false,
+    key: None,
+};
+
+fn with_reactor<R>(f: impl FnOnce(&mut Reactor) -> R) -> R {
+    let reactor = &raw mut REACTOR;
+    // The calculator runs a single thread and `f` never calls back into this function.
+    unsafe { f(&mut *reactor) }
+}
+
+/// Forgets what the futures were waiting for, before polling them again.
+fn start_round() {
+    with_reactor(|reactor| {
+        reactor.deadline = None;
+        reactor.wants_key = false;
+        reactor.yielded = false;
+    });
+}
+
+/// Runs futures on the calculator's single thread.
+///
+/// ```ignore
+/// let mut executor = Executor::new();
+/// executor.spawn(async {
+///     loop {
+///         blink_cursor();
+///         sleep(Duration::from_millis(500)).await;
+///     }
+/// });
+/// let name = executor.block_on(async {
+///     let mut name = String::new();
+///     while let Some(c) = next_key().await.to_char() {
+///         name.push(c);
+///     }
+///     name
+/// });
+/// ```
+#[derive(Default)]
+pub struct Executor {
+    tasks: Vec<Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+impl Executor {
+    /// Creates an executor without any task.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a task, run alongside the future given to [`Executor::block_on`].
+    ///
+    /// Tasks that are not done when `block_on` returns are kept for the next call.
+    pub fn spawn(&mut self, task: impl Future<Output = ()> + 'static) {
+        self.tasks.push(Box::pin(task));
+    }
+
+    /// Runs the spawned tasks until all of them are done.
+    pub fn run(&mut self) {
+        while !self.tasks.is_empty() {
+            start_round();
+            self.poll_tasks();
+            if !self.tasks.is_empty() {
+                self.wait();
+            }
+        }
+    }
+
+    /// Runs `future` and the spawned tasks until `future` is done.
+    pub fn block_on<F: Future>(&mut self, future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        loop {
+            start_round();
+            let mut cx = Context::from_waker(Waker::noop());
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            self.poll_tasks();
+            self.wait();
+        }
+    }
+
+    fn poll_tasks(&mut self) {
+        let mut cx = Context::from_waker(Waker::noop());
+        self.tasks
+            .retain_mut(|task| task.as_mut().poll(&mut cx).is_pending());
+    }
+
+    /// Blocks until something the futures are waiting for happens.
+    fn wait(&mut self) {
+        let (deadline, wants_key, yielded) =
+            with_reactor(|reactor| (reactor.deadline, reactor.wants_key, reactor.yielded));
+        // A key nobody was waiting for anymore is dropped.
+        with_reactor(|reactor| reactor.key = None);
+        if yielded {
+            return;
+        }
+        if wants_key {
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let duration = deadline.saturating_duration_since(Instant::now());
+                    duration.as_millis().min(i32::MAX as u128) as i32
+                }
+                None => i32::MAX,
+            };
+            let key = keyboard::wait_for_input(timeout);
+            with_reactor(|reactor| reactor.key = key);
+        } else if let Some(deadline) = deadline {
+            time::sleep_until(deadline);
+        }
+        // Otherwise, the futures wait for something unknown to the
+        // executor: poll them again.
+    }
+}
+
+/// Runs a future to completion.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    Executor::new().block_on(future)
+}
+
+/// A future completing after a duration, see [`sleep`].
+pub struct Sleep {
+    deadline: Instant,
+}
+
+/// Waits for the given duration.
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(Instant::now() + duration)
+}
+
+/// Waits until the given instant.
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep { deadline }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        with_reactor(|reactor| {
+            reactor.deadline = Some(match reactor.deadline {
+                Some(deadline) => deadline.min(self.deadline),
+                None => self.deadline,
+            });
+        });
+        Poll::Pending
+    }
+}
+
+/// A future completing with the next keypress, see [`next_key`].
+pub struct NextKey {
+    _private: (),
+}
+
+/// Waits for the next keypress.
+///
+/// If several tasks wait for a key at the same time, only the first one
+/// polled gets it.
+pub fn next_key() -> NextKey {
+    NextKey { _private: () }
+}
+
+impl Future for NextKey {
+    type Output = Key;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Key> {
+        with_reactor(|reactor| match reactor.key.take() {
+            Some(key) => Poll::Ready(key),
+            None => {
+                reactor.wants_key = true;
+                Poll::Pending
+            }
+        })
+    }
+}
+
+/// A future letting the other tasks run once, see [`yield_now`].
+pub struct YieldNow {
+    yielded: bool,
+}
+
+/// Lets the other tasks run before continuing.
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        with_reactor(|reactor| reactor.yielded = true);
+        Poll::Pending
+    }
+}
+
+/// A future that may have completed, keeping its output until it is taken.
+enum MaybeDone<F: Future> {
+    Pending(F),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> MaybeDone<F> {
+    /// Polls the future if it is pending, returns true once it is done.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        // SAFETY: the future is never moved, it is dropped in place once done.
+        let this = unsafe { self.get_unchecked_mut() };
+        if let Self::Pending(future) = this
+            && let Poll::Ready(output) = unsafe { Pin::new_unchecked(future) }.poll(cx)
+        {
+            *this = Self::Done(output);
+        }
+        matches!(this, Self::Done(_))
+    }
+
+    /// Takes the output of a done future.
+    fn take(self: Pin<&mut Self>) -> F::Output {
+        // SAFETY: only called once the future is done, the output is not pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        match core::mem::replace(this, Self::Taken) {
+            Self::Done(output) => output,
+            _ => panic!("the future is not done"),
+        }
+    }
+}
+
+/// A future running two futures concurrently, see [`join`].
+pub struct Join<A: Future, B: Future> {
+    a: MaybeDone<A>,
+    b: MaybeDone<B>,
+}
+
+/// Runs two futures concurrently and waits for both of them.
+pub fn join<A: Future, B: Future>(a: A, b: B) -> Join<A, B> {
+    Join {
+        a: MaybeDone::Pending(a),
+        b: MaybeDone::Pending(b),
+    }
+}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: the fields are never moved out of `self`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut a = unsafe { Pin::new_unchecked(&mut this.a) };
+        let mut b = unsafe { Pin::new_unchecked(&mut this.b) };
+        let a_done = a.as_mut().poll(cx);
+        let b_done = b.as_mut().poll(cx);
+        if a_done && b_done {
+            Poll::Ready((a.take(), b.take()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// The output of a [`select`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// A future running two futures until one of them completes, see [`select`].
+pub struct Select<A, B> {
+    a: A,
+    b: B,
+}
+
+/// Runs two futures concurrently until one of them completes.
+///
+/// The other one is dropped. If both complete at the same time, `a` wins.
+pub fn select<A: Future, B: Future>(a: A, b: B) -> Select<A, B> {
+    Select { a, b }
+}
+
+impl<A: Future, B: Future> Future for Select<A, B> {
+    type Output = Either<A::Output, B::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: the fields are never moved out of `self`.
+        let this = unsafe { self.get_unchecked_mut() };
+        if let Poll::Ready(output) = unsafe { Pin::new_unchecked(&mut this.a) }.poll(cx) {
+            return Poll::Ready(Either::Left(output));
+        }
+        if let Poll::Ready(output) = unsafe { Pin::new_unchecked(&mut this.b) }.poll(cx) {
+            return Poll::Ready(Either::Right(output));
+        }
+        Poll::Pending
+    }
+}
+
+/// Runs a future until it completes or `duration` elapses.
+///
+/// Returns `None` on timeout.
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    match select(future, sleep(duration)).await {
+        Either::Left(output) => Some(output),
+        Either::Right(()) => None,
+    }
+}
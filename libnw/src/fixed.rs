@@ -0,0 +1,537 @@
+extern crate alloc;
+
+use alloc::format;
+use core::fmt::{self, Debug, Display};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// The number of steps in the sine table, for a quarter of a turn.
+const SINE_STEPS: usize = 256;
+
+/// Sine of [0, π/2] in Q16.16, computed at compile time so the values
+/// are the same on every machine.
+static SINE_TABLE: [i32; SINE_STEPS + 1] = sine_table();
+
+const fn sine_table() -> [i32; SINE_STEPS + 1] {
+    let mut table = [0; SINE_STEPS + 1];
+    let mut i = 0;
+    while i <= SINE_STEPS {
+        let x = core::f64::consts::FRAC_PI_2 * i as f64 / SINE_STEPS as f64;
+        // Taylor series, precise enough on [0, π/2] after 12 terms.
+        let mut term = x;
+        let mut sum = x;
+        let mut n = 1;
+        while n < 12 {
+            term = -term * x * x / ((2 * n) * (2 * n + 1)) as f64;
+            sum += term;
+            n += 1;
+        }
+        table[i] = (sum * 65536.0 + 0.5) as i32;
+        i += 1;
+    }
+    table
+}
+
+/// Sine of a binary angle (a full turn being 2³²), in Q16.16.
+fn sine_bam(phase: u32) -> i32 {
+    const QUARTER: u32 = 1 << 30;
+    let position = phase % QUARTER;
+    let (position, sign) = match phase / QUARTER {
+        0 => (position, 1),
+        1 => (QUARTER - position, 1),
+        2 => (position, -1),
+        _ => (QUARTER - position, -1),
+    };
+    // 8 bits of index and 16 bits of interpolation.
+    let index = (position >> 22) as usize;
+    if index == SINE_STEPS {
+        return sign * SINE_TABLE[SINE_STEPS];
+    }
+    let t = ((position >> 6) & 0xFFFF) as i64;
+    let (a, b) = (SINE_TABLE[index] as i64, SINE_TABLE[index + 1] as i64);
+    sign * (a + (((b - a) * t) >> 16)) as i32
+}
+
+/// Converts an angle in Q16.16 radians to a binary angle (a full turn being 2³²).
+fn radians_to_bam(radians: i32) -> u32 {
+    // 2³² / 2π, the `>> 16` removes the Q16.16 scaling of the radians.
+    const TURN_PER_RADIAN: i64 = 683_565_276;
+    ((radians as i64 * TURN_PER_RADIAN) >> 16) as u32
+}
+
+macro_rules! fixed {
+    (
+        $(#[$meta:meta])*
+        $name:ident($int:ty, $wide:ty, $unsigned_wide:ty, $frac_bits:expr, $digits:expr)
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        pub struct $name($int);
+
+        impl $name {
+            /// The number of bits after the point.
+            pub const FRAC_BITS: u32 = $frac_bits;
+            /// 0
+            pub const ZERO: Self = Self(0);
+            /// 1
+            pub const ONE: Self = Self(1 << $frac_bits);
+            /// 0.5
+            pub const HALF: Self = Self(1 << ($frac_bits - 1));
+            /// The smallest positive value.
+            pub const EPSILON: Self = Self(1);
+            /// The largest value.
+            pub const MAX: Self = Self(<$int>::MAX);
+            /// The smallest (most negative) value.
+            pub const MIN: Self = Self(<$int>::MIN);
+            /// π
+            pub const PI: Self = Self::from_bits(
+                (205_887 >> (16 - $frac_bits)) as $int,
+            );
+
+            /// Creates a number from its raw bits.
+            pub const fn from_bits(bits: $int) -> Self {
+                Self(bits)
+            }
+
+            /// The raw bits of the number.
+            pub const fn to_bits(self) -> $int {
+                self.0
+            }
+
+            /// Converts an integer, wrapping if it doesn't fit.
+            pub const fn from_int(value: $int) -> Self {
+                Self(value.wrapping_shl($frac_bits))
+            }
+
+            /// Converts a float, rounding to the nearest value and saturating
+            /// if it doesn't fit.
+            pub fn from_f32(value: f32) -> Self {
+                let scaled = value * (1u32 << $frac_bits) as f32;
+                let rounded = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+                Self(rounded as $int)
+            }
+
+            /// Converts to a float, exactly.
+            pub fn to_f32(self) -> f32 {
+                self.0 as f32 / (1u32 << $frac_bits) as f32
+            }
+
+            /// The largest integer less than or equal to the number.
+            pub const fn to_int(self) -> $int {
+                self.0 >> $frac_bits
+            }
+
+            /// Rounds towards minus infinity.
+            pub const fn floor(self) -> Self {
+                Self(self.0 & !((1 << $frac_bits) - 1))
+            }
+
+            /// Rounds towards plus infinity, wrapping if it doesn't fit.
+            pub const fn ceil(self) -> Self {
+                Self(self.0.wrapping_add((1 << $frac_bits) - 1)).floor()
+            }
+
+            /// Rounds to the nearest integer, half away from zero.
+            pub const fn round(self) -> Self {
+                if self.0 < 0 {
+                    Self(self.0.wrapping_neg().wrapping_add(Self::HALF.0)).floor().wrapping_neg()
+                } else {
+                    Self(self.0.wrapping_add(Self::HALF.0)).floor()
+                }
+            }
+
+            /// The part after the point, always positive.
+            pub const fn frac(self) -> Self {
+                Self(self.0 & ((1 << $frac_bits) - 1))
+            }
+
+            /// The absolute value, wrapping for [`Self::MIN`].
+            pub const fn abs(self) -> Self {
+                Self(self.0.wrapping_abs())
+            }
+
+            /// -1, 0 or 1 depending on the sign of the number.
+            pub const fn signum(self) -> Self {
+                Self::from_int(self.0.signum())
+            }
+
+            /// Checks if the number is strictly negative.
+            pub const fn is_negative(self) -> bool {
+                self.0 < 0
+            }
+
+            /// Adds, returns `None` on overflow.
+            pub const fn checked_add(self, other: Self) -> Option<Self> {
+                match self.0.checked_add(other.0) {
+                    Some(bits) => Some(Self(bits)),
+                    None => None,
+                }
+            }
+
+            /// Subtracts, returns `None` on overflow.
+            pub const fn checked_sub(self, other: Self) -> Option<Self> {
+                match self.0.checked_sub(other.0) {
+                    Some(bits) => Some(Self(bits)),
+                    None => None,
+                }
+            }
+
+            /// Multiplies, returns `None` on overflow.
+            pub const fn checked_mul(self, other: Self) -> Option<Self> {
+                let product = (self.0 as $wide * other.0 as $wide) >> $frac_bits;
+                if product > <$int>::MAX as $wide || product < <$int>::MIN as $wide {
+                    None
+                } else {
+                    Some(Self(product as $int))
+                }
+            }
+
+            /// Divides, returns `None` on overflow or division by zero.
+            pub const fn checked_div(self, other: Self) -> Option<Self> {
+                if other.0 == 0 {
+                    return None;
+                }
+                let quotient = ((self.0 as $wide) << $frac_bits) / other.0 as $wide;
+                if quotient > <$int>::MAX as $wide || quotient < <$int>::MIN as $wide {
+                    None
+                } else {
+                    Some(Self(quotient as $int))
+                }
+            }
+
+            /// Adds, staying at the bounds on overflow.
+            pub const fn saturating_add(self, other: Self) -> Self {
+                Self(self.0.saturating_add(other.0))
+            }
+
+            /// Subtracts, staying at the bounds on overflow.
+            pub const fn saturating_sub(self, other: Self) -> Self {
+                Self(self.0.saturating_sub(other.0))
+            }
+
+            /// Multiplies, staying at the bounds on overflow.
+            pub const fn saturating_mul(self, other: Self) -> Self {
+                match self.checked_mul(other) {
+                    Some(product) => product,
+                    None if (self.0 < 0) != (other.0 < 0) => Self::MIN,
+                    None => Self::MAX,
+                }
+            }
+
+            /// Divides, staying at the bounds on overflow.
+            ///
+            /// # Panics
+            /// Panics if `other` is zero.
+            pub const fn saturating_div(self, other: Self) -> Self {
+                assert!(other.0 != 0, "attempt to divide by zero");
+                match self.checked_div(other) {
+                    Some(quotient) => quotient,
+                    None if (self.0 < 0) != (other.0 < 0) => Self::MIN,
+                    None => Self::MAX,
+                }
+            }
+
+            /// Adds, wrapping around on overflow.
+            pub const fn wrapping_add(self, other: Self) -> Self {
+                Self(self.0.wrapping_add(other.0))
+            }
+
+            /// Subtracts, wrapping around on overflow.
+            pub const fn wrapping_sub(self, other: Self) -> Self {
+                Self(self.0.wrapping_sub(other.0))
+            }
+
+            /// Multiplies, wrapping around on overflow.
+            pub const fn wrapping_mul(self, other: Self) -> Self {
+                Self(((self.0 as $wide * other.0 as $wide) >> $frac_bits) as $int)
+            }
+
+            /// Divides, wrapping around on overflow.
+            ///
+            /// # Panics
+            /// Panics if `other` is zero.
+            pub const fn wrapping_div(self, other: Self) -> Self {
+                Self((((self.0 as $wide) << $frac_bits) / other.0 as $wide) as $int)
+            }
+
+            /// Negates, wrapping for [`Self::MIN`].
+            pub const fn wrapping_neg(self) -> Self {
+                Self(self.0.wrapping_neg())
+            }
+
+            /// The square root, rounded down. Negative numbers give 0.
+            pub const fn sqrt(self) -> Self {
+                if self.0 <= 0 {
+                    return Self::ZERO;
+                }
+                let scaled = (self.0 as $unsigned_wide) << $frac_bits;
+                Self(scaled.isqrt() as $int)
+            }
+
+            /// The sine of an angle in radians, from a lookup table.
+            pub fn sin(self) -> Self {
+                Self::from_q16(sine_bam(radians_to_bam(self.to_q16())))
+            }
+
+            /// The cosine of an angle in radians, from a lookup table.
+            pub fn cos(self) -> Self {
+                const QUARTER_TURN: u32 = 1 << 30;
+                let phase = radians_to_bam(self.to_q16()).wrapping_add(QUARTER_TURN);
+                Self::from_q16(sine_bam(phase))
+            }
+
+            /// Converts to Q16.16 bits, for the shared trigonometry.
+            const fn to_q16(self) -> i32 {
+                (self.0 as i32) << (16 - $frac_bits)
+            }
+
+            /// Converts from Q16.16 bits, for the shared trigonometry.
+            const fn from_q16(bits: i32) -> Self {
+                Self((bits >> (16 - $frac_bits)) as $int)
+            }
+        }
+
+        /// Overflowing panics if overflow checks are enabled, wraps otherwise.
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self(self.0 + other.0)
+            }
+        }
+
+        /// Overflowing panics if overflow checks are enabled, wraps otherwise.
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self(self.0 - other.0)
+            }
+        }
+
+        /// Overflowing panics if debug assertions are enabled, wraps otherwise.
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                debug_assert!(
+                    self.checked_mul(other).is_some(),
+                    "attempt to multiply with overflow"
+                );
+                self.wrapping_mul(other)
+            }
+        }
+
+        /// Overflowing panics if debug assertions are enabled, wraps otherwise.
+        impl Div for $name {
+            type Output = Self;
+
+            fn div(self, other: Self) -> Self {
+                debug_assert!(
+                    other.0 == 0 || self.checked_div(other).is_some(),
+                    "attempt to divide with overflow"
+                );
+                self.wrapping_div(other)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign for $name {
+            fn mul_assign(&mut self, other: Self) {
+                *self = *self * other;
+            }
+        }
+
+        impl DivAssign for $name {
+            fn div_assign(&mut self, other: Self) {
+                *self = *self / other;
+            }
+        }
+
+        impl From<$name> for f32 {
+            fn from(value: $name) -> f32 {
+                value.to_f32()
+            }
+        }
+
+        /// Writes the number in decimal, with the precision if given
+        /// (`{:.2}`), otherwise with just enough digits.
+        impl Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let digits = f.precision().unwrap_or($digits).min(9);
+                let ten_pow = 10u64.pow(digits as u32);
+                let magnitude = (self.0 as i64).unsigned_abs();
+                // Rounds to the nearest decimal.
+                let half = 1u64 << ($frac_bits - 1);
+                let scaled = (magnitude * ten_pow + half) >> $frac_bits;
+                let (int, mut frac) = (scaled / ten_pow, scaled % ten_pow);
+
+                let mut digits = digits;
+                if f.precision().is_none() {
+                    while digits > 0 && frac % 10 == 0 {
+                        frac /= 10;
+                        digits -= 1;
+                    }
+                }
+                let text = if digits == 0 {
+                    format!("{int}")
+                } else {
+                    format!("{int}.{frac:0digits$}")
+                };
+                f.pad_integral(self.0 >= 0 || scaled == 0, "", &text)
+            }
+        }
+
+        impl Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Display::fmt(self, f)
+            }
+        }
+    };
+}
+
+fixed!(
+    /// A Q16.16 fixed-point number: 16 bits of integer and 16 bits of fraction.
+    ///
+    /// Ranges from -32768 to 32767.99998, with a step of 1/65536. Unlike floats,
+    /// the results are the same on every machine, so physics computed on the host
+    /// and on the calculator match bit for bit.
+    I16F16(i32, i64, u64, 16, 5)
+);
+
+fixed!(
+    /// A Q8.8 fixed-point number: 8 bits of integer and 8 bits of fraction.
+    ///
+    /// Ranges from -128 to 127.996, with a step of 1/256. Half the size of
+    /// [`I16F16`], for large arrays where the precision is enough.
+    I8F8(i16, i32, u32, 8, 3)
+);
+
+impl From<i16> for I16F16 {
+    fn from(value: i16) -> Self {
+        Self::from_int(value as i32)
+    }
+}
+
+impl From<i8> for I8F8 {
+    fn from(value: i8) -> Self {
+        Self::from_int(value as i16)
+    }
+}
+
+impl From<I8F8> for I16F16 {
+    fn from(value: I8F8) -> Self {
+        Self::from_bits((value.to_bits() as i32) << 8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn mul_and_div() {
+        let three = I16F16::from_int(3);
+        assert_eq!(three * I16F16::HALF, I16F16::from_bits(0x1_8000));
+        assert_eq!(-three * I16F16::HALF, I16F16::from_bits(-0x1_8000));
+        assert_eq!(
+            I16F16::from_int(7) / I16F16::from_int(2),
+            I16F16::from_bits(0x3_8000)
+        );
+        assert_eq!(I16F16::ONE / three, I16F16::from_bits(21845));
+        assert_eq!(
+            I8F8::from_int(5) * I8F8::from_f32(0.25),
+            I8F8::from_bits(320)
+        );
+    }
+
+    #[test]
+    fn saturating_edges() {
+        let two = I16F16::from_int(2);
+        assert_eq!(I16F16::MAX.checked_add(I16F16::EPSILON), None);
+        assert_eq!(I16F16::MAX.saturating_add(I16F16::EPSILON), I16F16::MAX);
+        assert_eq!(I16F16::MIN.saturating_sub(I16F16::EPSILON), I16F16::MIN);
+        assert_eq!(I16F16::MAX.checked_mul(two), None);
+        assert_eq!(I16F16::MAX.saturating_mul(two), I16F16::MAX);
+        assert_eq!(I16F16::MIN.saturating_mul(two), I16F16::MIN);
+        assert_eq!(I16F16::MAX.saturating_mul(-two), I16F16::MIN);
+        assert_eq!(I16F16::MAX.saturating_div(I16F16::HALF), I16F16::MAX);
+        assert_eq!(I16F16::MIN.saturating_div(I16F16::HALF), I16F16::MIN);
+        assert_eq!(I16F16::MIN.checked_div(-I16F16::ONE), None);
+        assert_eq!(I16F16::MIN.saturating_div(-I16F16::ONE), I16F16::MAX);
+        assert_eq!(I8F8::MAX.saturating_mul(I8F8::from_int(-4)), I8F8::MIN);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(I16F16::ONE.checked_div(I16F16::ZERO), None);
+        assert_eq!(I8F8::ONE.checked_div(I8F8::ZERO), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn saturating_division_by_zero() {
+        let _ = I16F16::ONE.saturating_div(I16F16::ZERO);
+    }
+
+    #[test]
+    fn sqrt() {
+        assert_eq!(I16F16::from_int(4).sqrt(), I16F16::from_int(2));
+        assert_eq!(I16F16::ONE.sqrt(), I16F16::ONE);
+        assert_eq!(I16F16::from_int(2).sqrt(), I16F16::from_bits(92681));
+        assert_eq!(I16F16::MAX.sqrt(), I16F16::from_bits(11_863_283));
+        assert_eq!(I16F16::from_int(-4).sqrt(), I16F16::ZERO);
+        assert_eq!(I8F8::from_int(9).sqrt(), I8F8::from_int(3));
+    }
+
+    #[test]
+    fn sine_table() {
+        assert_eq!(SINE_TABLE[0], 0);
+        assert_eq!(SINE_TABLE[SINE_STEPS / 2], 46341);
+        assert_eq!(SINE_TABLE[SINE_STEPS], 65536);
+    }
+
+    #[test]
+    fn sin_and_cos() {
+        assert_eq!(I16F16::ZERO.sin(), I16F16::ZERO);
+        assert_eq!(I16F16::ZERO.cos(), I16F16::ONE);
+        assert_eq!(I16F16::ONE.sin(), I16F16::from_bits(55146));
+        assert_eq!(I16F16::ONE.cos(), I16F16::from_bits(35409));
+        assert_eq!((-I16F16::ONE).sin(), I16F16::from_bits(-55146));
+        assert_eq!(I16F16::PI.cos(), I16F16::from_bits(-65535));
+        assert_eq!(I16F16::from_int(3).sin(), I16F16::from_bits(9248));
+        assert_eq!(I16F16::from_int(3).cos(), I16F16::from_bits(-64879));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{}", I16F16::from_f32(-1.5)), "-1.5");
+        assert_eq!(format!("{}", I16F16::from_int(-3)), "-3");
+        assert_eq!(format!("{}", I16F16::HALF), "0.5");
+        assert_eq!(format!("{}", I16F16::from_bits(1)), "0.00002");
+        assert_eq!(format!("{}", I16F16::from_bits(-1)), "-0.00002");
+        assert_eq!(format!("{:.2}", I16F16::from_f32(-0.125)), "-0.13");
+        assert_eq!(format!("{:.1}", I16F16::from_f32(-0.01)), "0.0");
+        assert_eq!(format!("{}", I8F8::from_f32(-0.5)), "-0.5");
+    }
+}
@@ -0,0 +1,141 @@
+use core::ops::ControlFlow;
+
+use crate::display;
+use crate::time::{self, Duration, Instant};
+
+/// The callbacks of a game run by a [`GameLoop`].
+pub trait Game {
+    /// Advances the game by one fixed step of `dt` seconds.
+    ///
+    /// Read the keyboard here. Return `ControlFlow::Break(())` to stop the loop.
+    fn update(&mut self, dt: f32) -> ControlFlow<()>;
+
+    /// Draws the current state of the game.
+    ///
+    /// `alpha` is how far the time is between the last update and the next one,
+    /// from 0 to 1, to interpolate positions and get smooth movements even when
+    /// the update rate is low.
+    fn render(&mut self, alpha: f32, stats: &FrameStats);
+}
+
+/// Measurements of the last frames.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameStats {
+    /// The number of frames rendered per second, measured over the last second.
+    pub fps: f32,
+    /// The time spent updating and rendering the last frame, without the
+    /// time spent waiting for the screen refresh or for the next frame.
+    pub frame_time: Duration,
+    /// The number of updates run during the last frame.
+    pub updates: u32,
+}
+
+/// Runs a [`Game`] with a fixed update rate and a capped frame rate.
+///
+/// The game runs at the same speed whatever the time spent rendering:
+/// if a frame is slow, several updates are run before the next one.
+pub struct GameLoop {
+    timestep: Duration,
+    min_frame_time: Option<Duration>,
+    vsync: bool,
+    max_updates_per_frame: u32,
+    stats: FrameStats,
+}
+
+impl GameLoop {
+    /// Creates a loop updating the game `updates_per_second` times per second.
+    ///
+    /// The frame rate is not capped and vsync is disabled.
+    pub fn new(updates_per_second: u32) -> Self {
+        Self {
+            timestep: Duration::from_secs(1) / updates_per_second.max(1),
+            min_frame_time: None,
+            vsync: false,
+            max_updates_per_frame: 5,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Sleeps between frames so no more than `fps` frames are rendered per second.
+    pub fn with_max_fps(mut self, fps: u32) -> Self {
+        self.min_frame_time = Some(Duration::from_secs(1) / fps.max(1));
+        self
+    }
+
+    /// Waits for the screen refresh before rendering, to avoid tearing.
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// Limits the number of updates run to catch up after a slow frame.
+    ///
+    /// When the limit is reached, the game slows down instead of spending
+    /// all its time catching up.
+    pub fn with_max_updates_per_frame(mut self, max: u32) -> Self {
+        self.max_updates_per_frame = max.max(1);
+        self
+    }
+
+    /// The duration of one update.
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    /// Measurements of the last frames.
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Runs the game until [`Game::update`] breaks.
+    pub fn run<G: Game>(&mut self, game: &mut G) {
+        let dt = self.timestep.as_secs_f32();
+        let mut accumulator = Duration::ZERO;
+        let mut previous = Instant::now();
+        let mut fps_start = previous;
+        let mut fps_frames = 0u32;
+
+        loop {
+            let frame_start = Instant::now();
+            accumulator += frame_start.saturating_duration_since(previous);
+            previous = frame_start;
+
+            let mut updates = 0;
+            while accumulator >= self.timestep {
+                if updates == self.max_updates_per_frame {
+                    // Too far behind, drop the remaining time.
+                    accumulator = Duration::ZERO;
+                    break;
+                }
+                if game.update(dt).is_break() {
+                    return;
+                }
+                accumulator -= self.timestep;
+                updates += 1;
+            }
+            self.stats.updates = updates;
+
+            let mut vblank_wait = Duration::ZERO;
+            if self.vsync {
+                let wait_start = Instant::now();
+                display::wait_for_vblank();
+                vblank_wait = wait_start.elapsed();
+            }
+            let alpha = accumulator.as_secs_f32() / dt;
+            game.render(alpha, &self.stats);
+            self.stats.frame_time = frame_start.elapsed().saturating_sub(vblank_wait);
+
+            fps_frames += 1;
+            let fps_window = fps_start.elapsed();
+            if fps_window >= Duration::from_secs(1) {
+                self.stats.fps = fps_frames as f32 / fps_window.as_secs_f32();
+                fps_start = Instant::now();
+                fps_frames = 0;
+            }
+
+            if let Some(min_frame_time) = self.min_frame_time {
+                time::sleep_until(frame_start + min_frame_time);
+            }
+        }
+    }
+}
@@ -0,0 +1,423 @@
+extern crate alloc;
+
+use alloc::{format, vec::Vec};
+
+use crate::display::{self, CHAR_HEIGHT, CHAR_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::keyboard::{self, Key, KeyboardState, RawKey};
+use crate::theme;
+use crate::time::{self, Duration, Instant};
+
+/// How long [`InputMap::capture`] waits for a keypress before giving up.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The version of the format written by [`InputMap::save`].
+const SAVE_VERSION: u8 = 1;
+
+/// What triggers an action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    /// A physical key, held as long as it is pressed.
+    Raw(RawKey),
+    /// A key event, with its modifiers, only active on the frame it is received.
+    Key(Key),
+}
+
+impl Binding {
+    fn encode(&self) -> [u8; 2] {
+        match self {
+            Self::Raw(key) => [0, *key as u8],
+            // Key events all fit in a byte.
+            Self::Key(key) => [1, *key as u16 as u8],
+        }
+    }
+
+    fn decode(bytes: [u8; 2]) -> Option<Self> {
+        match bytes {
+            [0, value] => RawKey::from_u8(value).map(Self::Raw),
+            [1, value] => Key::from_u16(value as u16).map(Self::Key),
+            _ => None,
+        }
+    }
+}
+
+impl From<RawKey> for Binding {
+    fn from(key: RawKey) -> Self {
+        Self::Raw(key)
+    }
+}
+
+impl From<Key> for Binding {
+    fn from(key: Key) -> Self {
+        Self::Key(key)
+    }
+}
+
+/// Why saved bindings couldn't be loaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// The data ends in the middle of a binding.
+    Truncated,
+    /// The data was written by another version of libnw.
+    UnknownVersion,
+    /// The data doesn't have the same number of actions as the map.
+    ActionCountMismatch,
+    /// The data contains a key that doesn't exist.
+    InvalidBinding,
+}
+
+/// Why bindings couldn't be saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaveError {
+    /// There are more than 255 actions.
+    TooManyActions,
+    /// An action has more than 255 bindings.
+    TooManyBindings,
+}
+
+struct Action<A> {
+    action: A,
+    name: &'static str,
+    bindings: Vec<Binding>,
+}
+
+/// Maps the actions of the app (`Jump`, `Confirm`...) to the keys triggering them.
+///
+/// Call [`InputMap::update`] once per frame, then query the actions.
+///
+/// ```ignore
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Action { Jump, Cancel }
+///
+/// let mut input = InputMap::new()
+///     .with_action(Action::Jump, "Jump", &[RawKey::Ok.into(), RawKey::Up.into()])
+///     .with_action(Action::Cancel, "Cancel", &[RawKey::Back.into()]);
+/// loop {
+///     input.update();
+///     if input.just_pressed(Action::Jump) { /* ... */ }
+/// }
+/// ```
+pub struct InputMap<A> {
+    actions: Vec<Action<A>>,
+    previous: KeyboardState,
+    current: KeyboardState,
+    /// The key events received during the current frame.
+    events: Vec<Key>,
+}
+
+impl<A: Copy + PartialEq> Default for InputMap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Copy + PartialEq> InputMap<A> {
+    /// Creates a map without any action.
+    pub fn new() -> Self {
+        Self {
+            actions: Vec::new(),
+            previous: KeyboardState(0),
+            current: KeyboardState(0),
+            events: Vec::new(),
+        }
+    }
+
+    /// Declares an action with its default bindings.
+    ///
+    /// The name is shown by [`InputMap::capture`].
+    pub fn with_action(mut self, action: A, name: &'static str, bindings: &[Binding]) -> Self {
+        self.add_action(action, name, bindings);
+        self
+    }
+
+    /// Declares an action with its default bindings, replacing it if it already exists.
+    pub fn add_action(&mut self, action: A, name: &'static str, bindings: &[Binding]) {
+        let bindings = bindings.to_vec();
+        match self.find_mut(action) {
+            Some(existing) => {
+                existing.name = name;
+                existing.bindings = bindings;
+            }
+            None => self.actions.push(Action {
+                action,
+                name,
+                bindings,
+            }),
+        }
+    }
+
+    /// The bindings of an action, empty if it wasn't declared.
+    pub fn bindings(&self, action: A) -> &[Binding] {
+        self.find(action)
+            .map(|entry| entry.bindings.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The name of an action, if it was declared.
+    pub fn name(&self, action: A) -> Option<&'static str> {
+        self.find(action).map(|entry| entry.name)
+    }
+
+    /// Adds a binding to an action.
+    pub fn bind(&mut self, action: A, binding: Binding) {
+        if let Some(entry) = self.find_mut(action)
+            && !entry.bindings.contains(&binding)
+        {
+            entry.bindings.push(binding);
+        }
+    }
+
+    /// Removes a binding from an action.
+    pub fn unbind(&mut self, action: A, binding: Binding) {
+        if let Some(entry) = self.find_mut(action) {
+            entry.bindings.retain(|other| *other != binding);
+        }
+    }
+
+    /// Replaces all the bindings of an action by a single one.
+    pub fn rebind(&mut self, action: A, binding: Binding) {
+        if let Some(entry) = self.find_mut(action) {
+            entry.bindings.clear();
+            entry.bindings.push(binding);
+        }
+    }
+
+    /// Reads the keyboard for a new frame.
+    ///
+    /// This also consumes the pending key events, so don't mix it with
+    /// [`keyboard::wait_for_input`]. If the app already reads events,
+    /// use [`InputMap::update_with_events`] instead.
+    pub fn update(&mut self) {
+        self.update_with_events(core::iter::from_fn(|| keyboard::wait_for_input(0)));
+    }
+
+    /// Reads the keyboard for a new frame, with the events read by the app.
+    pub fn update_with_events(&mut self, events: impl IntoIterator<Item = Key>) {
+        self.previous = self.current;
+        self.current = keyboard::scan();
+        self.events.clear();
+        self.events.extend(events);
+    }
+
+    /// Checks if the action is active during this frame.
+    pub fn is_held(&self, action: A) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Raw(key) => self.current.is_down(key),
+            Binding::Key(key) => self.events.contains(&key),
+        })
+    }
+
+    /// Checks if the action started during this frame.
+    pub fn just_pressed(&self, action: A) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Raw(key) => self.current.is_down(key) && !self.previous.is_down(key),
+            Binding::Key(key) => self.events.contains(&key),
+        })
+    }
+
+    /// Checks if the action stopped during this frame.
+    ///
+    /// Only physical key bindings can be released.
+    pub fn just_released(&self, action: A) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Raw(key) => !self.current.is_down(key) && self.previous.is_down(key),
+            Binding::Key(_) => false,
+        })
+    }
+
+    /// Returns the first action triggered by a key event, if any.
+    pub fn action_for(&self, key: Key) -> Option<A> {
+        self.actions
+            .iter()
+            .find(|entry| {
+                entry.bindings.iter().any(|binding| match binding {
+                    Binding::Key(other) => *other == key,
+                    Binding::Raw(raw) => key.raw_key() == Some(*raw),
+                })
+            })
+            .map(|entry| entry.action)
+    }
+
+    /// Shows a "press a key" screen and binds the pressed key to the action.
+    ///
+    /// Gives up after a few seconds without any keypress, in which case the
+    /// bindings are left untouched and `None` is returned.
+    pub fn capture(&mut self, action: A) -> Option<RawKey> {
+        let name = self.name(action)?;
+        display::clear(theme::current().background);
+        draw_centered(&format!("Press a key for {name}"), -1);
+        draw_centered("or wait to cancel", 1);
+
+        // Don't capture the key that opened this screen.
+        wait_for_release();
+        let deadline = Instant::now() + CAPTURE_TIMEOUT;
+        let captured = loop {
+            if let Some(key) = keyboard::scan().pressed().next() {
+                break Some(key);
+            }
+            if Instant::now() >= deadline {
+                break None;
+            }
+            time::msleep(10);
+        };
+        // Don't trigger the new binding on the next frame.
+        wait_for_release();
+        self.previous = keyboard::scan();
+        self.current = self.previous;
+        // The firmware queued the keypress as an event too, drop it.
+        while keyboard::wait_for_input(0).is_some() {}
+
+        let key = captured?;
+        self.rebind(action, Binding::Raw(key));
+        Some(key)
+    }
+
+    /// Serializes the bindings, so they can be restored with [`InputMap::load`].
+    ///
+    /// Actions are written in the order they were declared. The format can't
+    /// store more than 255 actions, or more than 255 bindings for an action.
+    pub fn save(&self) -> Result<Vec<u8>, SaveError> {
+        let mut data = Vec::new();
+        data.push(SAVE_VERSION);
+        data.push(u8::try_from(self.actions.len()).map_err(|_| SaveError::TooManyActions)?);
+        for entry in &self.actions {
+            let count =
+                u8::try_from(entry.bindings.len()).map_err(|_| SaveError::TooManyBindings)?;
+            data.push(count);
+            for binding in &entry.bindings {
+                data.extend_from_slice(&binding.encode());
+            }
+        }
+        Ok(data)
+    }
+
+    /// Restores bindings written by [`InputMap::save`].
+    ///
+    /// The actions must have been declared in the same order as when saving.
+    /// On error, the bindings are left untouched.
+    pub fn load(&mut self, data: &[u8]) -> Result<(), LoadError> {
+        let mut bytes = data.iter().copied();
+        let mut next = || bytes.next().ok_or(LoadError::Truncated);
+
+        if next()? != SAVE_VERSION {
+            return Err(LoadError::UnknownVersion);
+        }
+        if next()? as usize != self.actions.len() {
+            return Err(LoadError::ActionCountMismatch);
+        }
+        let mut loaded = Vec::with_capacity(self.actions.len());
+        for _ in 0..self.actions.len() {
+            let count = next()?;
+            let mut bindings = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let binding = Binding::decode([next()?, next()?]);
+                bindings.push(binding.ok_or(LoadError::InvalidBinding)?);
+            }
+            loaded.push(bindings);
+        }
+        for (entry, bindings) in self.actions.iter_mut().zip(loaded) {
+            entry.bindings = bindings;
+        }
+        Ok(())
+    }
+
+    fn find(&self, action: A) -> Option<&Action<A>> {
+        self.actions.iter().find(|entry| entry.action == action)
+    }
+
+    fn find_mut(&mut self, action: A) -> Option<&mut Action<A>> {
+        self.actions.iter_mut().find(|entry| entry.action == action)
+    }
+
+    fn any_binding(&self, action: A, f: impl Fn(Binding) -> bool) -> bool {
+        self.bindings(action).iter().any(|binding| f(*binding))
+    }
+}
+
+/// Blocks until no key is pressed.
+fn wait_for_release() {
+    while keyboard::scan().pressed().next().is_some() {
+        time::msleep(10);
+    }
+}
+
+/// Draws a line of text centered horizontally, `line` lines away from the middle of the screen.
+fn draw_centered(text: &str, line: i16) {
+    let theme = theme::current();
+    let width = text.chars().count() as u16 * CHAR_WIDTH;
+    let y = (SCREEN_HEIGHT - CHAR_HEIGHT) as i16 / 2 + line * CHAR_HEIGHT as i16;
+    display::draw_string(
+        text,
+        SCREEN_WIDTH.saturating_sub(width) / 2,
+        y as u16,
+        false,
+        theme.text,
+        theme.background,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Action {
+        Jump,
+        Cancel,
+    }
+
+    fn map() -> InputMap<Action> {
+        InputMap::new()
+            .with_action(Action::Jump, "Jump", &[RawKey::Ok.into(), Key::Up.into()])
+            .with_action(Action::Cancel, "Cancel", &[RawKey::Back.into()])
+    }
+
+    #[test]
+    fn save_and_load() {
+        let mut saved = map();
+        saved.rebind(Action::Jump, Binding::Raw(RawKey::Up));
+        saved.bind(Action::Cancel, Binding::Key(Key::Back));
+        let data = saved.save().unwrap();
+
+        let mut loaded = map();
+        assert_eq!(loaded.load(&data), Ok(()));
+        assert_eq!(loaded.bindings(Action::Jump), saved.bindings(Action::Jump));
+        assert_eq!(
+            loaded.bindings(Action::Cancel),
+            saved.bindings(Action::Cancel)
+        );
+    }
+
+    #[test]
+    fn save_too_many_bindings() {
+        let mut map = map();
+        map.add_action(Action::Jump, "Jump", &[Binding::Raw(RawKey::Ok); 256]);
+        assert_eq!(map.save(), Err(SaveError::TooManyBindings));
+    }
+
+    #[test]
+    fn load_errors() {
+        let data = map().save().unwrap();
+        let mut map = map();
+        let original = map.bindings(Action::Jump).to_vec();
+
+        assert_eq!(map.load(&[]), Err(LoadError::Truncated));
+        assert_eq!(map.load(&data[..data.len() - 1]), Err(LoadError::Truncated));
+
+        let mut other_version = data.clone();
+        other_version[0] = SAVE_VERSION + 1;
+        assert_eq!(map.load(&other_version), Err(LoadError::UnknownVersion));
+
+        let mut fewer_actions = data.clone();
+        fewer_actions[1] = 1;
+        assert_eq!(
+            map.load(&fewer_actions),
+            Err(LoadError::ActionCountMismatch)
+        );
+
+        let mut invalid = data.clone();
+        invalid[3] = 2;
+        assert_eq!(map.load(&invalid), Err(LoadError::InvalidBinding));
+
+        assert_eq!(map.bindings(Action::Jump), original);
+    }
+}
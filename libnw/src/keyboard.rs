@@ -5,6 +5,7 @@ use core::fmt::Display;
 
 /// A hardware key
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RawKey {
     Left = 0,
     Up = 1,
@@ -54,11 +55,146 @@ pub enum RawKey {
     Exe = 52,
 }
 
+impl RawKey {
+    /// Iterates over all the keys of the keypad, from top left to bottom right.
+    pub fn iter() -> impl Iterator<Item = RawKey> {
+        LEGENDS.iter().map(|legend| legend.key)
+    }
+
+    /// Returns the key with the given scan code.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::iter().find(|key| *key as u8 == value)
+    }
+
+    /// The row of the key in the keypad matrix.
+    ///
+    /// Rows 0 and 1 are the navigation keys at the top of the calculator
+    /// (arrows, `Ok`, `Back`, `Home` and `OnOff`), the next ones match the
+    /// printed rows, from `Shift` to `Exe`.
+    pub fn row(&self) -> u8 {
+        *self as u8 / KEYS_PER_ROW
+    }
+
+    /// The column of the key in the keypad matrix, starting from the left.
+    pub fn column(&self) -> u8 {
+        *self as u8 % KEYS_PER_ROW
+    }
+
+    /// The main legend printed on the key.
+    pub fn label(&self) -> &'static str {
+        self.legend().label
+    }
+
+    /// The legend of the key when pressed after `Shift`, if any.
+    pub fn shift_label(&self) -> Option<&'static str> {
+        self.legend().shift
+    }
+
+    /// The legend of the key when pressed after `Alpha`, if any.
+    pub fn alpha_label(&self) -> Option<&'static str> {
+        self.legend().alpha
+    }
+
+    /// The event sent when the key is pressed with the given modifiers.
+    pub fn to_key(&self, shift: bool, alpha: bool) -> Option<Key> {
+        let layer = shift as u16 + 2 * alpha as u16;
+        Key::from_u16(*self as u16 + layer * KEYS_PER_LAYER)
+    }
+
+    fn legend(&self) -> &'static Legend {
+        // Every key has a legend.
+        LEGENDS.iter().find(|legend| legend.key == *self).unwrap()
+    }
+}
+
+/// The number of keys in a row of the keypad matrix.
+const KEYS_PER_ROW: u8 = 6;
+
+/// The offset between a key event and the same key with the next modifier.
+///
+/// Events are numbered as `raw key + layer * 54`, the layers being
+/// none, shift, alpha and shift + alpha.
+const KEYS_PER_LAYER: u16 = 54;
+
+/// What is printed on a key.
+struct Legend {
+    key: RawKey,
+    label: &'static str,
+    shift: Option<&'static str>,
+    alpha: Option<&'static str>,
+}
+
+impl Legend {
+    const fn new(
+        key: RawKey,
+        label: &'static str,
+        shift: Option<&'static str>,
+        alpha: Option<&'static str>,
+    ) -> Self {
+        Self {
+            key,
+            label,
+            shift,
+            alpha,
+        }
+    }
+}
+
+/// The legends of all the keys, in the order of their scan codes.
+const LEGENDS: &[Legend] = &[
+    Legend::new(RawKey::Left, "left", None, None),
+    Legend::new(RawKey::Up, "up", None, None),
+    Legend::new(RawKey::Down, "down", None, None),
+    Legend::new(RawKey::Right, "right", None, None),
+    Legend::new(RawKey::Ok, "OK", None, None),
+    Legend::new(RawKey::Back, "back", None, None),
+    Legend::new(RawKey::Home, "home", None, None),
+    Legend::new(RawKey::OnOff, "on/off", None, None),
+    Legend::new(RawKey::Shift, "shift", None, None),
+    Legend::new(RawKey::Alpha, "alpha", Some("lock"), None),
+    Legend::new(RawKey::Xnt, "x,n,t", Some("cut"), Some(":")),
+    Legend::new(RawKey::Var, "var", Some("copy"), Some(";")),
+    Legend::new(RawKey::Toolbox, "toolbox", Some("paste"), Some("\"")),
+    Legend::new(RawKey::Backspace, "del", Some("clear"), Some("%")),
+    Legend::new(RawKey::Exp, "e^x", Some("["), Some("a")),
+    Legend::new(RawKey::Ln, "ln", Some("]"), Some("b")),
+    Legend::new(RawKey::Log, "log", Some("{"), Some("c")),
+    Legend::new(RawKey::Imaginary, "i", Some("}"), Some("d")),
+    Legend::new(RawKey::Comma, ",", Some("_"), Some("e")),
+    Legend::new(RawKey::Power, "x^y", Some("sto"), Some("f")),
+    Legend::new(RawKey::Sine, "sin", Some("asin"), Some("g")),
+    Legend::new(RawKey::Cosine, "cos", Some("acos"), Some("h")),
+    Legend::new(RawKey::Tangent, "tan", Some("atan"), Some("i")),
+    Legend::new(RawKey::Pi, "π", Some("="), Some("j")),
+    Legend::new(RawKey::Sqrt, "√", Some("<"), Some("k")),
+    Legend::new(RawKey::Square, "x²", Some(">"), Some("l")),
+    Legend::new(RawKey::Seven, "7", None, Some("m")),
+    Legend::new(RawKey::Eight, "8", None, Some("n")),
+    Legend::new(RawKey::Nine, "9", None, Some("o")),
+    Legend::new(RawKey::LeftParenthesis, "(", None, Some("p")),
+    Legend::new(RawKey::RightParenthesis, ")", None, Some("q")),
+    Legend::new(RawKey::Four, "4", None, Some("r")),
+    Legend::new(RawKey::Five, "5", None, Some("s")),
+    Legend::new(RawKey::Six, "6", None, Some("t")),
+    Legend::new(RawKey::Multiplication, "×", None, Some("u")),
+    Legend::new(RawKey::Division, "÷", None, Some("v")),
+    Legend::new(RawKey::One, "1", None, Some("w")),
+    Legend::new(RawKey::Two, "2", None, Some("x")),
+    Legend::new(RawKey::Three, "3", None, Some("y")),
+    Legend::new(RawKey::Plus, "+", None, Some("z")),
+    Legend::new(RawKey::Minus, "-", None, Some("space")),
+    Legend::new(RawKey::Zero, "0", None, Some("?")),
+    Legend::new(RawKey::Dot, ".", None, Some("!")),
+    Legend::new(RawKey::Ee, "×10^x", None, None),
+    Legend::new(RawKey::Ans, "ans", None, None),
+    Legend::new(RawKey::Exe, "EXE", None, None),
+];
+
 /// A keypress event
 ///
 /// The result of pressing a key with or without modifiers (shift and alpha).
 #[repr(u16)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Key {
     Left = 0,
     Up = 1,
@@ -189,21 +325,163 @@ pub enum Key {
 }
 
 impl Key {
+    /// Every key event, sorted by value, except `None`.
+    pub const ALL: [Key; 124] = [
+        Self::Left,
+        Self::Up,
+        Self::Down,
+        Self::Right,
+        Self::Ok,
+        Self::Back,
+        Self::Shift,
+        Self::Alpha,
+        Self::Xnt,
+        Self::Var,
+        Self::Toolbox,
+        Self::Backspace,
+        Self::Exp,
+        Self::Ln,
+        Self::Log,
+        Self::Imaginary,
+        Self::Comma,
+        Self::Power,
+        Self::Sine,
+        Self::Cosine,
+        Self::Tangent,
+        Self::Pi,
+        Self::Sqrt,
+        Self::Square,
+        Self::Seven,
+        Self::Eight,
+        Self::Nine,
+        Self::LeftParenthesis,
+        Self::RightParenthesis,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Multiplication,
+        Self::Division,
+        Self::One,
+        Self::Two,
+        Self::Three,
+        Self::Plus,
+        Self::Minus,
+        Self::Zero,
+        Self::Dot,
+        Self::Ee,
+        Self::Ans,
+        Self::Exe,
+        Self::ShiftLeft,
+        Self::ShiftUp,
+        Self::ShiftDown,
+        Self::ShiftRight,
+        Self::AlphaLock,
+        Self::Cut,
+        Self::Copy,
+        Self::Paste,
+        Self::Clear,
+        Self::LeftBracket,
+        Self::RightBracket,
+        Self::LeftBrace,
+        Self::RightBrace,
+        Self::Underscore,
+        Self::Sto,
+        Self::Arcsine,
+        Self::Arccosine,
+        Self::Arctangent,
+        Self::Equal,
+        Self::Lower,
+        Self::Greater,
+        Self::Colon,
+        Self::Semicolon,
+        Self::DoubleQuotes,
+        Self::Percent,
+        Self::LowerA,
+        Self::LowerB,
+        Self::LowerC,
+        Self::LowerD,
+        Self::LowerE,
+        Self::LowerF,
+        Self::LowerG,
+        Self::LowerH,
+        Self::LowerI,
+        Self::LowerJ,
+        Self::LowerK,
+        Self::LowerL,
+        Self::LowerM,
+        Self::LowerN,
+        Self::LowerO,
+        Self::LowerP,
+        Self::LowerQ,
+        Self::LowerR,
+        Self::LowerS,
+        Self::LowerT,
+        Self::LowerU,
+        Self::LowerV,
+        Self::LowerW,
+        Self::LowerX,
+        Self::LowerY,
+        Self::LowerZ,
+        Self::Space,
+        Self::Question,
+        Self::Exclamation,
+        Self::UpperA,
+        Self::UpperB,
+        Self::UpperC,
+        Self::UpperD,
+        Self::UpperE,
+        Self::UpperF,
+        Self::UpperG,
+        Self::UpperH,
+        Self::UpperI,
+        Self::UpperJ,
+        Self::UpperK,
+        Self::UpperL,
+        Self::UpperM,
+        Self::UpperN,
+        Self::UpperO,
+        Self::UpperP,
+        Self::UpperQ,
+        Self::UpperR,
+        Self::UpperS,
+        Self::UpperT,
+        Self::UpperU,
+        Self::UpperV,
+        Self::UpperW,
+        Self::UpperX,
+        Self::UpperY,
+        Self::UpperZ,
+    ];
+
+    /// Returns the event with the given value.
+    pub fn from_u16(value: u16) -> Option<Self> {
+        Self::ALL
+            .binary_search_by_key(&value, |key| *key as u16)
+            .ok()
+            .map(|index| Self::ALL[index])
+    }
+
+    /// The physical key that sends this event, regardless of the modifiers.
+    pub fn raw_key(&self) -> Option<RawKey> {
+        RawKey::from_u8((*self as u16 % KEYS_PER_LAYER) as u8)
+    }
+
+    /// Checks if the event is sent with `Shift` (including `Shift` + `Alpha`).
+    pub fn is_shifted(&self) -> bool {
+        (*self as u16 / KEYS_PER_LAYER) % 2 == 1
+    }
+
+    /// Checks if the event is sent with `Alpha` (including `Shift` + `Alpha`).
+    pub fn is_alpha(&self) -> bool {
+        *self as u16 / KEYS_PER_LAYER >= 2
+    }
+
+    /// Checks if the key is one of the ten digits.
     pub fn is_digit(&self) -> bool {
-        matches!(
-            self,
-            Self::One
-                | Self::Two
-                | Self::Three
-                | Self::Four
-                | Self::Five
-                | Self::Six
-                | Self::Seven
-                | Self::Nine
-                | Self::Zero
-        )
+        self.to_digit().is_some()
     }
 
+    /// Returns the value of the digit, if the key is a digit.
     pub fn to_digit(&self) -> Option<u8> {
         match self {
             Self::Zero => Some(0),
@@ -219,6 +497,184 @@ impl Key {
             _ => None,
         }
     }
+
+    /// Returns the character this key would insert in a text field.
+    ///
+    /// Keys that don't insert exactly one character (arrows, `Sine`, `Exp`...)
+    /// return `None`.
+    pub fn to_char(&self) -> Option<char> {
+        CHARS.iter().find(|(key, _)| key == self).map(|(_, c)| *c)
+    }
+
+    /// Returns the key that inserts the given character.
+    ///
+    /// This is the reverse of [`Key::to_char`].
+    pub fn from_char(c: char) -> Option<Self> {
+        CHARS
+            .iter()
+            .find(|(_, other)| *other == c)
+            .map(|(key, _)| *key)
+    }
+
+    /// Checks if the key is a lowercase letter.
+    pub fn is_lowercase(&self) -> bool {
+        (Self::LowerA as u16..=Self::LowerZ as u16).contains(&(*self as u16))
+    }
+
+    /// Checks if the key is an uppercase letter.
+    pub fn is_uppercase(&self) -> bool {
+        (Self::UpperA as u16..=Self::UpperZ as u16).contains(&(*self as u16))
+    }
+
+    /// Checks if the key is a letter, lowercase or uppercase.
+    pub fn is_letter(&self) -> bool {
+        self.is_lowercase() || self.is_uppercase()
+    }
+
+    /// Checks if the key is an arrow, with or without shift.
+    pub fn is_arrow(&self) -> bool {
+        matches!(
+            self,
+            Self::Left
+                | Self::Up
+                | Self::Down
+                | Self::Right
+                | Self::ShiftLeft
+                | Self::ShiftUp
+                | Self::ShiftDown
+                | Self::ShiftRight
+        )
+    }
+
+    /// Checks if the key changes the layer of the next keypress (shift and alpha).
+    pub fn is_modifier(&self) -> bool {
+        matches!(self, Self::Shift | Self::Alpha | Self::AlphaLock)
+    }
+
+    /// Checks if the key edits text without inserting a character
+    /// (deletion and clipboard).
+    pub fn is_editing(&self) -> bool {
+        matches!(
+            self,
+            Self::Backspace | Self::Clear | Self::Cut | Self::Copy | Self::Paste
+        )
+    }
+}
+
+/// The characters inserted by keys.
+///
+/// When several keys insert the same character (`LowerI` and `Imaginary`),
+/// the first one is returned by [`Key::from_char`].
+const CHARS: &[(Key, char)] = &[
+    (Key::Zero, '0'),
+    (Key::One, '1'),
+    (Key::Two, '2'),
+    (Key::Three, '3'),
+    (Key::Four, '4'),
+    (Key::Five, '5'),
+    (Key::Six, '6'),
+    (Key::Seven, '7'),
+    (Key::Eight, '8'),
+    (Key::Nine, '9'),
+    (Key::LowerA, 'a'),
+    (Key::LowerB, 'b'),
+    (Key::LowerC, 'c'),
+    (Key::LowerD, 'd'),
+    (Key::LowerE, 'e'),
+    (Key::LowerF, 'f'),
+    (Key::LowerG, 'g'),
+    (Key::LowerH, 'h'),
+    (Key::LowerI, 'i'),
+    (Key::LowerJ, 'j'),
+    (Key::LowerK, 'k'),
+    (Key::LowerL, 'l'),
+    (Key::LowerM, 'm'),
+    (Key::LowerN, 'n'),
+    (Key::LowerO, 'o'),
+    (Key::LowerP, 'p'),
+    (Key::LowerQ, 'q'),
+    (Key::LowerR, 'r'),
+    (Key::LowerS, 's'),
+    (Key::LowerT, 't'),
+    (Key::LowerU, 'u'),
+    (Key::LowerV, 'v'),
+    (Key::LowerW, 'w'),
+    (Key::LowerX, 'x'),
+    (Key::LowerY, 'y'),
+    (Key::LowerZ, 'z'),
+    (Key::UpperA, 'A'),
+    (Key::UpperB, 'B'),
+    (Key::UpperC, 'C'),
+    (Key::UpperD, 'D'),
+    (Key::UpperE, 'E'),
+    (Key::UpperF, 'F'),
+    (Key::UpperG, 'G'),
+    (Key::UpperH, 'H'),
+    (Key::UpperI, 'I'),
+    (Key::UpperJ, 'J'),
+    (Key::UpperK, 'K'),
+    (Key::UpperL, 'L'),
+    (Key::UpperM, 'M'),
+    (Key::UpperN, 'N'),
+    (Key::UpperO, 'O'),
+    (Key::UpperP, 'P'),
+    (Key::UpperQ, 'Q'),
+    (Key::UpperR, 'R'),
+    (Key::UpperS, 'S'),
+    (Key::UpperT, 'T'),
+    (Key::UpperU, 'U'),
+    (Key::UpperV, 'V'),
+    (Key::UpperW, 'W'),
+    (Key::UpperX, 'X'),
+    (Key::UpperY, 'Y'),
+    (Key::UpperZ, 'Z'),
+    (Key::Space, ' '),
+    (Key::Dot, '.'),
+    (Key::Comma, ','),
+    (Key::Plus, '+'),
+    (Key::Minus, '-'),
+    (Key::Multiplication, '*'),
+    (Key::Division, '/'),
+    (Key::Power, '^'),
+    (Key::Equal, '='),
+    (Key::Lower, '<'),
+    (Key::Greater, '>'),
+    (Key::LeftParenthesis, '('),
+    (Key::RightParenthesis, ')'),
+    (Key::LeftBracket, '['),
+    (Key::RightBracket, ']'),
+    (Key::LeftBrace, '{'),
+    (Key::RightBrace, '}'),
+    (Key::Underscore, '_'),
+    (Key::Colon, ':'),
+    (Key::Semicolon, ';'),
+    (Key::DoubleQuotes, '"'),
+    (Key::Percent, '%'),
+    (Key::Question, '?'),
+    (Key::Exclamation, '!'),
+    (Key::Imaginary, 'i'),
+    (Key::Pi, 'π'),
+    (Key::Sqrt, '√'),
+    (Key::Ee, 'ᴇ'),
+    (Key::Sto, '→'),
+];
+
+/// The event sent by the key without any modifier.
+impl TryFrom<RawKey> for Key {
+    type Error = RawKey;
+
+    fn try_from(raw_key: RawKey) -> Result<Self, Self::Error> {
+        raw_key.to_key(false, false).ok_or(raw_key)
+    }
+}
+
+/// The physical key that sends the event.
+impl TryFrom<Key> for RawKey {
+    type Error = Key;
+
+    fn try_from(key: Key) -> Result<Self, Self::Error> {
+        key.raw_key().ok_or(key)
+    }
 }
 
 impl Display for Key {
@@ -230,8 +686,22 @@ impl Display for Key {
 
 /// The state of the keyboard (pressed keys)
 #[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct KeyboardState(pub u64);
 
+impl KeyboardState {
+    /// Checks if the key is pressed in this state.
+    pub fn is_down(&self, key: RawKey) -> bool {
+        eadk::keyboard_key_down(*self, key)
+    }
+
+    /// Iterates over the pressed keys.
+    pub fn pressed(&self) -> impl Iterator<Item = RawKey> {
+        let state = *self;
+        RawKey::iter().filter(move |key| state.is_down(*key))
+    }
+}
+
 pub use eadk::scan;
 
 /// Waits for `timeout` or until a key is pressed.
@@ -244,6 +714,15 @@ pub fn wait_for_input(timeout_ms: i32) -> Option<Key> {
     }
 }
 
+/// Waits until a key is pressed, without any timeout.
+pub fn wait_for_key() -> Key {
+    loop {
+        if let Some(key) = wait_for_input(i32::MAX) {
+            return key;
+        }
+    }
+}
+
 /// Checks if the given key is pressed.
 pub fn is_pressed(key: RawKey) -> bool {
     let keyboard_state = eadk::scan();
@@ -0,0 +1,339 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use crate::display::Rect;
+use crate::ui::Widget;
+
+/// How much space a child takes along an axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Size {
+    /// A number of pixels.
+    Fixed(u16),
+    /// A share of the space left by the other children, weighted with the other
+    /// `Fill` children: `Fill(2)` gets twice as much as `Fill(1)`.
+    Fill(u16),
+    /// A fraction of the space of the parent, `Fraction(1, 3)` is a third.
+    Fraction(u16, u16),
+}
+
+/// Where a child is placed when it doesn't take all the space it could.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Takes all the space, whatever its size.
+    #[default]
+    Stretch,
+}
+
+/// How the children of a [`Layout`] are placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// From left to right.
+    Row,
+    /// From top to bottom.
+    Column,
+    /// On top of each other.
+    Stack,
+}
+
+/// Computes the rects of children placed in a row, a column or a stack.
+///
+/// Each child has a size along the layout (its width in a row, its height in a
+/// column) and a size across it, which is aligned with [`Layout::with_align`].
+/// In a stack, the two sizes are the width and the height, both aligned.
+/// Layouts are nested by splitting one of the rects of a parent layout again.
+///
+/// ```ignore
+/// let rects = Layout::column()
+///     .with_padding(8)
+///     .with_spacing(4)
+///     .with(Size::Fixed(ui::TITLE_BAR_HEIGHT))
+///     .with(Size::Fill(1))
+///     .with_sized(Size::Fixed(ui::ROW_HEIGHT), Size::Fraction(1, 2))
+///     .with_align(Align::Center)
+///     .split(Rect::SCREEN);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Layout {
+    direction: Direction,
+    padding: u16,
+    spacing: u16,
+    align: Align,
+    justify: Align,
+    children: Vec<(Size, Size)>,
+}
+
+impl Layout {
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            padding: 0,
+            spacing: 0,
+            align: Align::default(),
+            justify: Align::Start,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn row() -> Self {
+        Self::new(Direction::Row)
+    }
+
+    pub fn column() -> Self {
+        Self::new(Direction::Column)
+    }
+
+    pub fn stack() -> Self {
+        Self::new(Direction::Stack)
+    }
+
+    /// Sets the space kept empty on every side of the layout.
+    pub fn with_padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the space between two children. Unused in a stack.
+    pub fn with_spacing(mut self, spacing: u16) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Sets where the children are placed across the layout, `Stretch` by default.
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets where the children are placed along the layout when they don't
+    /// take all of it, `Start` by default. `Stretch` is the same as `Start`.
+    pub fn with_justify(mut self, justify: Align) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    /// Adds a child taking all the space across the layout.
+    pub fn with(self, size: Size) -> Self {
+        self.with_sized(size, Size::Fill(1))
+    }
+
+    /// Adds a child with a size along and across the layout.
+    pub fn with_sized(mut self, along: Size, across: Size) -> Self {
+        self.children.push((along, across));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Computes the rects of the children in the given rect, in order.
+    pub fn split(&self, rect: Rect) -> Vec<Rect> {
+        let inner = Rect::new(
+            rect.x.saturating_add(self.padding),
+            rect.y.saturating_add(self.padding),
+            rect.width.saturating_sub(self.padding.saturating_mul(2)),
+            rect.height.saturating_sub(self.padding.saturating_mul(2)),
+        );
+        match self.direction {
+            Direction::Row => self
+                .split_along(inner.x, inner.width, inner.y, inner.height)
+                .map(|(x, width, y, height)| Rect::new(x, y, width, height))
+                .collect(),
+            Direction::Column => self
+                .split_along(inner.y, inner.height, inner.x, inner.width)
+                .map(|(y, height, x, width)| Rect::new(x, y, width, height))
+                .collect(),
+            Direction::Stack => self
+                .children
+                .iter()
+                .map(|&(width, height)| {
+                    let (x, width) = place(inner.x, inner.width, width, self.align);
+                    let (y, height) = place(inner.y, inner.height, height, self.align);
+                    Rect::new(x, y, width, height)
+                })
+                .collect(),
+        }
+    }
+
+    /// Computes the rects of the children and gives them to the widgets, in order.
+    ///
+    /// Widgets after the last child are left where they are.
+    pub fn arrange(&self, rect: Rect, widgets: &mut [&mut dyn Widget]) {
+        for (widget, rect) in widgets.iter_mut().zip(self.split(rect)) {
+            widget.set_rect(rect);
+        }
+    }
+
+    /// Splits a row or a column, as `(start, length)` along the layout and
+    /// `(start, length)` across it for each child.
+    fn split_along(
+        &self,
+        start: u16,
+        length: u16,
+        cross_start: u16,
+        cross_length: u16,
+    ) -> impl Iterator<Item = (u16, u16, u16, u16)> + '_ {
+        let gaps = self.spacing as u32 * self.children.len().saturating_sub(1) as u32;
+        let available = (length as u32).saturating_sub(gaps);
+
+        let mut used = 0;
+        let mut weights = 0;
+        for &(size, _) in &self.children {
+            match size {
+                Size::Fill(weight) => weights += weight as u32,
+                size => used += resolve(size, available),
+            }
+        }
+        let mut remaining = available.saturating_sub(used);
+
+        // Without any child to fill it, the space left moves the children.
+        let mut position = start as u32;
+        if weights == 0 {
+            position += match self.justify {
+                Align::Start | Align::Stretch => 0,
+                Align::Center => remaining / 2,
+                Align::End => remaining,
+            };
+        }
+
+        let end = start as u32 + length as u32;
+        let mut weights_left = weights;
+        self.children.iter().map(move |&(along, across)| {
+            let size = match along {
+                Size::Fill(weight) => {
+                    // The last filling child gets what's left after rounding.
+                    let share = (remaining * weight as u32)
+                        .checked_div(weights_left)
+                        .unwrap_or(0);
+                    remaining -= share;
+                    weights_left -= weight as u32;
+                    share
+                }
+                size => resolve(size, available),
+            };
+            let child_start = position.min(end);
+            let child_size = size.min(end - child_start);
+            position += size + self.spacing as u32;
+            let (cross, cross_size) = place(cross_start, cross_length, across, self.align);
+            (child_start as u16, child_size as u16, cross, cross_size)
+        })
+    }
+}
+
+/// The size in pixels of a `Fixed` or `Fraction` size out of `available` pixels.
+fn resolve(size: Size, available: u32) -> u32 {
+    match size {
+        Size::Fixed(pixels) => pixels as u32,
+        Size::Fraction(numerator, denominator) => (available * numerator as u32)
+            .checked_div(denominator as u32)
+            .unwrap_or(0)
+            .min(available),
+        Size::Fill(_) => available,
+    }
+}
+
+/// Places a child of the given size in `length` pixels starting at `start`.
+fn place(start: u16, length: u16, size: Size, align: Align) -> (u16, u16) {
+    let size = resolve(size, length as u32).min(length as u32) as u16;
+    match align {
+        Align::Start => (start, size),
+        Align::Center => (start + (length - size) / 2, size),
+        Align::End => (start + length - size, size),
+        Align::Stretch => (start, length),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECT: Rect = Rect {
+        x: 10,
+        y: 20,
+        width: 100,
+        height: 50,
+    };
+
+    #[test]
+    fn fill_shares_the_remainder() {
+        let rects = Layout::row()
+            .with(Size::Fill(1))
+            .with(Size::Fill(1))
+            .with(Size::Fill(1))
+            .split(RECT);
+        let widths: Vec<u16> = rects.iter().map(|rect| rect.width).collect();
+        assert_eq!(widths, [33, 33, 34]);
+        assert_eq!(rects[1].x, 43);
+        assert_eq!(rects[2].x + rects[2].width, 110);
+        assert!(rects.iter().all(|rect| rect.y == 20 && rect.height == 50));
+    }
+
+    #[test]
+    fn fixed_fraction_and_weights() {
+        let rects = Layout::column()
+            .with_padding(5)
+            .with_spacing(2)
+            .with(Size::Fixed(10))
+            .with(Size::Fraction(1, 4))
+            .with(Size::Fill(2))
+            .with(Size::Fill(1))
+            .split(RECT);
+        // 40 pixels inside the padding, 6 of them between the children.
+        let heights: Vec<u16> = rects.iter().map(|rect| rect.height).collect();
+        assert_eq!(heights, [10, 8, 10, 6]);
+        let tops: Vec<u16> = rects.iter().map(|rect| rect.y).collect();
+        assert_eq!(tops, [25, 37, 47, 59]);
+        assert!(rects.iter().all(|rect| rect.x == 15 && rect.width == 90));
+    }
+
+    #[test]
+    fn justify_and_align() {
+        let layout = Layout::row()
+            .with_sized(Size::Fixed(20), Size::Fixed(10))
+            .with_sized(Size::Fixed(30), Size::Fraction(1, 2));
+        let end = layout.clone().with_justify(Align::End).split(RECT);
+        assert_eq!((end[0].x, end[1].x), (60, 80));
+        let center = layout
+            .with_justify(Align::Center)
+            .with_align(Align::Center)
+            .split(RECT);
+        assert_eq!((center[0].x, center[1].x), (35, 55));
+        assert_eq!((center[0].y, center[0].height), (40, 10));
+        assert_eq!((center[1].y, center[1].height), (32, 25));
+    }
+
+    #[test]
+    fn clipped_at_the_end() {
+        let rects = Layout::row()
+            .with(Size::Fixed(80))
+            .with(Size::Fixed(80))
+            .with(Size::Fixed(80))
+            .split(RECT);
+        assert_eq!((rects[1].x, rects[1].width), (90, 20));
+        assert_eq!((rects[2].x, rects[2].width), (110, 0));
+    }
+
+    #[test]
+    fn stack_and_huge_padding() {
+        let rects = Layout::stack()
+            .with_align(Align::End)
+            .with_sized(Size::Fixed(10), Size::Fill(1))
+            .split(RECT);
+        let rect = rects[0];
+        assert_eq!((rect.x, rect.y, rect.width, rect.height), (100, 20, 10, 50));
+
+        let rects = Layout::row()
+            .with_padding(u16::MAX)
+            .with(Size::Fill(1))
+            .split(RECT);
+        assert_eq!((rects[0].width, rects[0].height), (0, 0));
+    }
+}
@@ -20,6 +20,24 @@ pub mod display;
 /// Get random values.
 pub mod random;
 
+/// Fixed-point numbers, giving the same results on every machine without an FPU.
+pub mod fixed;
+
+/// 2D and 3D vectors and matrices, in floating or fixed point.
+pub mod math;
+
+/// A software 3D renderer: perspective, depth buffer, wireframe and shaded meshes.
+pub mod render3d;
+
+/// Graphs of functions and data, with zoom, pan and a trace cursor.
+pub mod plot;
+
+/// Bar, line, pie charts and histograms, drawn on the screen or in a buffer.
+pub mod charts;
+
+/// Coherent noise (value, Perlin, simplex), for terrains, clouds and other textures.
+pub mod noise;
+
 /// Day 13, you are now able to control the time.
 pub mod time;
 
@@ -32,5 +50,47 @@ pub mod backlight;
 /// Interfaces with the keyboard, retrieve raw state and wait for keypress.
 pub mod keyboard;
 
+/// Timers, and a cooperative scheduler running them while waiting for keypresses.
+pub mod scheduler;
+
+/// A minimal async runtime, to write multi-step flows as straight-line code.
+///
+/// Requires the `async` feature.
+#[cfg(feature = "async")]
+pub mod executor;
+
+/// Split the app into scenes (title screen, gameplay, pause menu...) run by a stack.
+pub mod scene;
+
+/// Run a game at a fixed update rate, whatever the cost of rendering.
+pub mod game;
+
+/// Map the actions of the app to keys, and let the user rebind them.
+pub mod input;
+
+/// Colors, font and spacing of the widgets, with a light and a dark theme.
+pub mod theme;
+
+/// A single line text field, to let the user type a name or a number.
+pub mod text_input;
+
+/// Widgets looking like the firmware apps: title bar, lists, toggles, sliders, buttons...
+pub mod ui;
+
+/// Scrolling list and table views, drawing only the visible rows of large data.
+pub mod views;
+
+/// Modal dialogs, confirmation boxes and toasts drawn over the app.
+pub mod dialog;
+
+/// Rows, columns and stacks computing the rects of widgets.
+pub mod layout;
+
+/// The header of the firmware apps, with the battery, the USB plug and a clock.
+pub mod status_bar;
+
+/// Dim and turn off the screen when the calculator isn't used, and warn of a low battery.
+pub mod power;
+
 /// Access the external data of the app.
 pub mod external;
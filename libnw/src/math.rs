@@ -0,0 +1,727 @@
+use core::f32::consts::PI;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::display::{Rect, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::fixed::{I8F8, I16F16};
+
+/// A number the vectors and matrices can be made of: `f32` or a fixed-point type.
+pub trait Scalar:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_f32(value: f32) -> Self;
+    fn to_f32(self) -> f32;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    /// The remainder of the division by `divisor`, between `-divisor` and `divisor`.
+    fn remainder(self, divisor: Self) -> Self;
+
+    fn abs(self) -> Self {
+        if self < Self::ZERO { -self } else { self }
+    }
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn sqrt(self) -> Self {
+        libm::sqrtf(self)
+    }
+
+    fn sin(self) -> Self {
+        libm::sinf(self)
+    }
+
+    fn cos(self) -> Self {
+        libm::cosf(self)
+    }
+
+    fn remainder(self, divisor: Self) -> Self {
+        libm::remainderf(self, divisor)
+    }
+}
+
+macro_rules! fixed_scalar {
+    ($name:ident) => {
+        impl Scalar for $name {
+            const ZERO: Self = $name::ZERO;
+            const ONE: Self = $name::ONE;
+
+            fn from_f32(value: f32) -> Self {
+                $name::from_f32(value)
+            }
+
+            fn to_f32(self) -> f32 {
+                $name::to_f32(self)
+            }
+
+            fn sqrt(self) -> Self {
+                $name::sqrt(self)
+            }
+
+            fn sin(self) -> Self {
+                $name::sin(self)
+            }
+
+            fn cos(self) -> Self {
+                $name::cos(self)
+            }
+
+            fn remainder(self, divisor: Self) -> Self {
+                // Dividing by zero, or `MIN` by -1, gives 0 instead of panicking.
+                let bits = self.to_bits().checked_rem(divisor.to_bits()).unwrap_or(0);
+                $name::from_bits(bits)
+            }
+        }
+    };
+}
+
+fixed_scalar!(I16F16);
+fixed_scalar!(I8F8);
+
+/// Brings an angle in radians into ]-π, π]. Infinite angles give NaN.
+pub fn wrap_angle<T: Scalar>(angle: T) -> T {
+    let pi = T::from_f32(PI);
+    let turn = pi + pi;
+    let angle = angle.remainder(turn);
+    if angle > pi {
+        angle - turn
+    } else if angle <= -pi {
+        angle + turn
+    } else {
+        angle
+    }
+}
+
+/// The smallest signed angle to turn from `from` to `to`, in ]-π, π].
+pub fn angle_difference<T: Scalar>(from: T, to: T) -> T {
+    wrap_angle(to - from)
+}
+
+/// The sine of a small angle (less than about 0.2 radians), approximated as the angle.
+pub fn small_angle_sin<T: Scalar>(angle: T) -> T {
+    angle
+}
+
+/// The cosine of a small angle (less than about 0.2 radians), approximated as 1 - angle²/2.
+pub fn small_angle_cos<T: Scalar>(angle: T) -> T {
+    T::ONE - angle * angle / T::from_f32(2.0)
+}
+
+/// A 2D vector, a position or a displacement on the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec2<T = f32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Scalar> Vec2<T> {
+    /// (0, 0)
+    pub const ZERO: Self = Self {
+        x: T::ZERO,
+        y: T::ZERO,
+    };
+
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// A vector with both coordinates set to `value`.
+    pub const fn splat(value: T) -> Self {
+        Self::new(value, value)
+    }
+
+    /// The unit vector pointing at `angle` radians, clockwise from the x axis
+    /// since the y axis of the screen points down.
+    pub fn from_angle(angle: T) -> Self {
+        Self::new(angle.cos(), angle.sin())
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z coordinate of the 3D cross product, positive if `other` is
+    /// clockwise from `self` on the screen.
+    pub fn perp_dot(self, other: Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// The vector rotated by a quarter turn.
+    pub fn perp(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(self, other: Self) -> T {
+        (other - self).length()
+    }
+
+    /// The vector with a length of 1, or zero if the vector is zero.
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length == T::ZERO {
+            return Self::ZERO;
+        }
+        self / length
+    }
+
+    /// Goes from `self` (`t = 0`) to `other` (`t = 1`).
+    pub fn lerp(self, other: Self, t: T) -> Self {
+        self + (other - self) * t
+    }
+
+    /// The vector rotated by `angle` radians.
+    pub fn rotate(self, angle: T) -> Self {
+        self.rotate_by(angle.cos(), angle.sin())
+    }
+
+    /// The vector rotated by the angle with the given cosine and sine,
+    /// to rotate many vectors without computing them again.
+    pub fn rotate_by(self, cos: T, sin: T) -> Self {
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Multiplies the coordinates one by one.
+    pub fn scale(self, factors: Self) -> Self {
+        Self::new(self.x * factors.x, self.y * factors.y)
+    }
+
+    /// Converts to another scalar type.
+    pub fn cast<U: Scalar>(self) -> Vec2<U> {
+        Vec2::new(U::from_f32(self.x.to_f32()), U::from_f32(self.y.to_f32()))
+    }
+
+    /// The top left corner of the rect.
+    pub fn origin_of(rect: Rect) -> Self {
+        Self::from_pixel(rect.x, rect.y)
+    }
+
+    /// The width and height of the rect.
+    pub fn size_of(rect: Rect) -> Self {
+        Self::from_pixel(rect.width, rect.height)
+    }
+
+    /// The center of the rect.
+    pub fn center_of(rect: Rect) -> Self {
+        Self::origin_of(rect) + Self::size_of(rect) / T::from_f32(2.0)
+    }
+
+    /// The position of a pixel.
+    pub fn from_pixel(x: u16, y: u16) -> Self {
+        Self::new(T::from_f32(x as f32), T::from_f32(y as f32))
+    }
+
+    /// The pixel at this position, or `None` if it is outside the screen.
+    pub fn to_pixel(self) -> Option<(u16, u16)> {
+        let x = libm::floorf(self.x.to_f32());
+        let y = libm::floorf(self.y.to_f32());
+        if (0.0..SCREEN_WIDTH as f32).contains(&x) && (0.0..SCREEN_HEIGHT as f32).contains(&y) {
+            Some((x as u16, y as u16))
+        } else {
+            None
+        }
+    }
+
+    /// The rect between two corners, clipped to the screen.
+    pub fn rect_between(self, other: Self) -> Rect {
+        let clamp_x = |v: T| libm::roundf(v.to_f32()).clamp(0.0, SCREEN_WIDTH as f32) as u16;
+        let clamp_y = |v: T| libm::roundf(v.to_f32()).clamp(0.0, SCREEN_HEIGHT as f32) as u16;
+        let (x0, x1) = (clamp_x(self.x), clamp_x(other.x));
+        let (y0, y1) = (clamp_y(self.y), clamp_y(other.y));
+        Rect::new(x0.min(x1), y0.min(y1), x0.abs_diff(x1), y0.abs_diff(y1))
+    }
+}
+
+impl<T: Scalar> Add for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Scalar> Sub for Vec2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Scalar> Mul<T> for Vec2<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Scalar> Div<T> for Vec2<T> {
+    type Output = Self;
+
+    fn div(self, divisor: T) -> Self {
+        Self::new(self.x / divisor, self.y / divisor)
+    }
+}
+
+impl<T: Scalar> Neg for Vec2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Scalar> AddAssign for Vec2<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> SubAssign for Vec2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Scalar> MulAssign<T> for Vec2<T> {
+    fn mul_assign(&mut self, factor: T) {
+        *self = *self * factor;
+    }
+}
+
+/// A 3D vector.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec3<T = f32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Scalar> Vec3<T> {
+    /// (0, 0, 0)
+    pub const ZERO: Self = Self {
+        x: T::ZERO,
+        y: T::ZERO,
+        z: T::ZERO,
+    };
+
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// A vector with all coordinates set to `value`.
+    pub const fn splat(value: T) -> Self {
+        Self::new(value, value, value)
+    }
+
+    /// The vector of the plane, with a z coordinate.
+    pub const fn extend(xy: Vec2<T>, z: T) -> Self {
+        Self::new(xy.x, xy.y, z)
+    }
+
+    /// The x and y coordinates.
+    pub const fn truncate(self) -> Vec2<T> {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(self, other: Self) -> T {
+        (other - self).length()
+    }
+
+    /// The vector with a length of 1, or zero if the vector is zero.
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length == T::ZERO {
+            return Self::ZERO;
+        }
+        self / length
+    }
+
+    /// Goes from `self` (`t = 0`) to `other` (`t = 1`).
+    pub fn lerp(self, other: Self, t: T) -> Self {
+        self + (other - self) * t
+    }
+
+    /// Multiplies the coordinates one by one.
+    pub fn scale(self, factors: Self) -> Self {
+        Self::new(self.x * factors.x, self.y * factors.y, self.z * factors.z)
+    }
+
+    /// Converts to another scalar type.
+    pub fn cast<U: Scalar>(self) -> Vec3<U> {
+        Vec3::new(
+            U::from_f32(self.x.to_f32()),
+            U::from_f32(self.y.to_f32()),
+            U::from_f32(self.z.to_f32()),
+        )
+    }
+}
+
+impl<T: Scalar> Add for Vec3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Scalar> Sub for Vec3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Scalar> Mul<T> for Vec3<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl<T: Scalar> Div<T> for Vec3<T> {
+    type Output = Self;
+
+    fn div(self, divisor: T) -> Self {
+        Self::new(self.x / divisor, self.y / divisor, self.z / divisor)
+    }
+}
+
+impl<T: Scalar> Neg for Vec3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Scalar> AddAssign for Vec3<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> SubAssign for Vec3<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Scalar> MulAssign<T> for Vec3<T> {
+    fn mul_assign(&mut self, factor: T) {
+        *self = *self * factor;
+    }
+}
+
+/// A 3x3 matrix, stored row by row.
+///
+/// It is either a 2D affine transform working on [`Vec2`]s (rotation, scale and
+/// translation, the last row being 0 0 1) or a 3D linear transform working on
+/// [`Vec3`]s (rotation and scale). Transforms are combined by multiplying them,
+/// `a * b` applying `b` first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat3<T = f32> {
+    pub rows: [[T; 3]; 3],
+}
+
+impl<T: Scalar> Mat3<T> {
+    /// The transform doing nothing.
+    pub const IDENTITY: Self = Self::from_rows([
+        [T::ONE, T::ZERO, T::ZERO],
+        [T::ZERO, T::ONE, T::ZERO],
+        [T::ZERO, T::ZERO, T::ONE],
+    ]);
+
+    pub const fn from_rows(rows: [[T; 3]; 3]) -> Self {
+        Self { rows }
+    }
+
+    /// Moves 2D points by `offset`.
+    pub fn translation(offset: Vec2<T>) -> Self {
+        let (o, i) = (T::ZERO, T::ONE);
+        Self::from_rows([[i, o, offset.x], [o, i, offset.y], [o, o, i]])
+    }
+
+    /// Scales 2D vectors, each coordinate by its own factor.
+    pub fn scale(factors: Vec2<T>) -> Self {
+        let (o, i) = (T::ZERO, T::ONE);
+        Self::from_rows([[factors.x, o, o], [o, factors.y, o], [o, o, i]])
+    }
+
+    /// Rotates 2D vectors by `angle` radians around the origin.
+    pub fn rotation(angle: T) -> Self {
+        let (o, i) = (T::ZERO, T::ONE);
+        let (cos, sin) = (angle.cos(), angle.sin());
+        Self::from_rows([[cos, -sin, o], [sin, cos, o], [o, o, i]])
+    }
+
+    /// Rotates 2D points by `angle` radians around `center`.
+    pub fn rotation_around(angle: T, center: Vec2<T>) -> Self {
+        Self::translation(center) * Self::rotation(angle) * Self::translation(-center)
+    }
+
+    /// Rotates 3D vectors by `angle` radians around the x axis.
+    pub fn rotation_x(angle: T) -> Self {
+        let (o, i) = (T::ZERO, T::ONE);
+        let (cos, sin) = (angle.cos(), angle.sin());
+        Self::from_rows([[i, o, o], [o, cos, -sin], [o, sin, cos]])
+    }
+
+    /// Rotates 3D vectors by `angle` radians around the y axis.
+    pub fn rotation_y(angle: T) -> Self {
+        let (o, i) = (T::ZERO, T::ONE);
+        let (cos, sin) = (angle.cos(), angle.sin());
+        Self::from_rows([[cos, o, sin], [o, i, o], [-sin, o, cos]])
+    }
+
+    /// Rotates 3D vectors by `angle` radians around the z axis.
+    pub fn rotation_z(angle: T) -> Self {
+        Self::rotation(angle)
+    }
+
+    /// Scales 3D vectors, each coordinate by its own factor.
+    pub fn scale3(factors: Vec3<T>) -> Self {
+        let o = T::ZERO;
+        Self::from_rows([[factors.x, o, o], [o, factors.y, o], [o, o, factors.z]])
+    }
+
+    /// Applies the transform to a 2D point, including the translation.
+    pub fn transform_point(&self, point: Vec2<T>) -> Vec2<T> {
+        let r = &self.rows;
+        Vec2::new(
+            r[0][0] * point.x + r[0][1] * point.y + r[0][2],
+            r[1][0] * point.x + r[1][1] * point.y + r[1][2],
+        )
+    }
+
+    /// Applies the transform to a 2D vector, ignoring the translation.
+    pub fn transform_vector(&self, vector: Vec2<T>) -> Vec2<T> {
+        let r = &self.rows;
+        Vec2::new(
+            r[0][0] * vector.x + r[0][1] * vector.y,
+            r[1][0] * vector.x + r[1][1] * vector.y,
+        )
+    }
+
+    /// Swaps rows and columns. For a rotation, this is its inverse.
+    pub fn transpose(&self) -> Self {
+        let r = &self.rows;
+        Self::from_rows([
+            [r[0][0], r[1][0], r[2][0]],
+            [r[0][1], r[1][1], r[2][1]],
+            [r[0][2], r[1][2], r[2][2]],
+        ])
+    }
+
+    pub fn determinant(&self) -> T {
+        let r = &self.rows;
+        r[0][0] * (r[1][1] * r[2][2] - r[1][2] * r[2][1])
+            - r[0][1] * (r[1][0] * r[2][2] - r[1][2] * r[2][0])
+            + r[0][2] * (r[1][0] * r[2][1] - r[1][1] * r[2][0])
+    }
+
+    /// The transform undoing this one, or `None` if it flattens space.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == T::ZERO {
+            return None;
+        }
+        let r = &self.rows;
+        let cofactor =
+            |a: usize, b: usize, c: usize, d: usize| r[a][b] * r[c][d] - r[a][d] * r[c][b];
+        let adjugate = Self::from_rows([
+            [
+                cofactor(1, 1, 2, 2),
+                -cofactor(0, 1, 2, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 0, 2, 2),
+                cofactor(0, 0, 2, 2),
+                -cofactor(0, 0, 1, 2),
+            ],
+            [
+                cofactor(1, 0, 2, 1),
+                -cofactor(0, 0, 2, 1),
+                cofactor(0, 0, 1, 1),
+            ],
+        ]);
+        let mut inverse = adjugate;
+        for row in inverse.rows.iter_mut() {
+            for value in row.iter_mut() {
+                *value = *value / determinant;
+            }
+        }
+        Some(inverse)
+    }
+}
+
+impl<T: Scalar> Default for Mat3<T> {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl<T: Scalar> Mul for Mat3<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut rows = [[T::ZERO; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                for k in 0..3 {
+                    *value += self.rows[i][k] * other.rows[k][j];
+                }
+            }
+        }
+        Self::from_rows(rows)
+    }
+}
+
+impl<T: Scalar> Mul<Vec3<T>> for Mat3<T> {
+    type Output = Vec3<T>;
+
+    fn mul(self, vector: Vec3<T>) -> Vec3<T> {
+        let r = &self.rows;
+        Vec3::new(
+            r[0][0] * vector.x + r[0][1] * vector.y + r[0][2] * vector.z,
+            r[1][0] * vector.x + r[1][1] * vector.y + r[1][2] * vector.z,
+            r[2][0] * vector.x + r[2][1] * vector.y + r[2][2] * vector.z,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn wrap_angle_f32() {
+        assert_eq!(wrap_angle(0.0f32), 0.0);
+        assert_close(wrap_angle(1.0f32), 1.0);
+        assert_close(wrap_angle(3.0 * PI), PI);
+        assert_close(wrap_angle(-PI), PI);
+        assert_close(wrap_angle(PI + 1.0), 1.0 - PI);
+        assert_close(wrap_angle(-7.0f32), 2.0 * PI - 7.0);
+    }
+
+    #[test]
+    fn wrap_angle_large_and_infinite() {
+        for angle in [1.3e8f32, -1.3e8, 1e30, f32::MAX, f32::MIN] {
+            let wrapped = wrap_angle(angle);
+            assert!(wrapped > -PI && wrapped <= PI, "{angle} wraps to {wrapped}");
+        }
+        assert!(wrap_angle(f32::INFINITY).is_nan());
+        assert!(wrap_angle(f32::NEG_INFINITY).is_nan());
+    }
+
+    #[test]
+    fn wrap_angle_fixed() {
+        let pi = I16F16::from_f32(PI);
+        for angle in [
+            I16F16::ZERO,
+            I16F16::from_int(4),
+            I16F16::from_int(-4),
+            I16F16::MAX,
+            I16F16::MIN,
+        ] {
+            let wrapped = wrap_angle(angle);
+            assert!(wrapped > -pi && wrapped <= pi, "{angle} wraps to {wrapped}");
+        }
+        assert_close(wrap_angle(I16F16::from_int(4)).to_f32(), 4.0 - 2.0 * PI);
+        // π only has 8 bits of fraction in I8F8.
+        let wrapped = wrap_angle(I8F8::from_int(-4)).to_f32();
+        assert!((wrapped - (2.0 * PI - 4.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn angle_difference_takes_the_short_way() {
+        assert_close(angle_difference(0.1f32, -0.1), -0.2);
+        assert_close(angle_difference(PI - 0.1, -PI + 0.1), 0.2);
+        assert_close(angle_difference(-PI + 0.1, PI - 0.1), -0.2);
+        assert_close(angle_difference(0.0f32, 1e9), wrap_angle(1e9f32));
+    }
+
+    #[test]
+    fn inverse() {
+        let transform = Mat3::translation(Vec2::new(3.0f32, -2.0))
+            * Mat3::rotation(0.5)
+            * Mat3::scale(Vec2::new(2.0, 4.0));
+        let inverse = transform.inverse().unwrap();
+        let identity = transform * inverse;
+        for (i, row) in identity.rows.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert_close(value, if i == j { 1.0 } else { 0.0 });
+            }
+        }
+        let point = Vec2::new(5.0, 7.0);
+        let back = inverse.transform_point(transform.transform_point(point));
+        assert_close(back.x, point.x);
+        assert_close(back.y, point.y);
+    }
+
+    #[test]
+    fn inverse_of_singular() {
+        assert!(Mat3::<f32>::scale(Vec2::new(1.0, 0.0)).inverse().is_none());
+        let zero = Mat3::from_rows([[I16F16::ZERO; 3]; 3]);
+        assert!(zero.inverse().is_none());
+    }
+}
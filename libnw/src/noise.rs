@@ -0,0 +1,564 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use crate::display::{Buffer, Color};
+use crate::random::{RandomSource, Rng};
+
+/// The kind of coherent noise to sample.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseKind {
+    /// Random values on a grid, smoothly interpolated. Cheap but blocky.
+    Value,
+    /// Random gradients on a grid (Ken Perlin's improved noise).
+    Perlin,
+    /// Random gradients on a grid of triangles. Fewer artifacts than Perlin noise.
+    Simplex,
+}
+
+/// How to layer octaves of noise in fractal brownian motion (fBm).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fractal {
+    /// The number of layers, each one adding finer details.
+    pub octaves: u32,
+    /// How much the frequency is multiplied from one octave to the next.
+    pub lacunarity: f32,
+    /// How much the amplitude is multiplied from one octave to the next.
+    pub gain: f32,
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+/// A seeded noise generator.
+///
+/// All the functions return values between -1 and 1, and the same seed always
+/// gives the same values. Integer coordinates are the corners of the grid:
+/// scale the coordinates down to get smoother noise.
+pub struct Noise {
+    /// A permutation of 0..256, repeated twice to avoid wrapping indices.
+    perm: [u8; 512],
+}
+
+impl Noise {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self::from_rng(&mut Rng::seed_from_u64(seed))
+    }
+
+    /// Creates a generator drawing its permutation table from `rng`.
+    pub fn from_rng(rng: &mut impl RandomSource) -> Self {
+        let mut table = [0u8; 256];
+        for (i, value) in table.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+        rng.shuffle(&mut table);
+        let mut perm = [0u8; 512];
+        perm[..256].copy_from_slice(&table);
+        perm[256..].copy_from_slice(&table);
+        Self { perm }
+    }
+
+    /// Samples one dimensional noise.
+    ///
+    /// There is no one dimensional simplex grid, [`NoiseKind::Simplex`]
+    /// uses a 1D variant of the simplex gradients.
+    pub fn sample1(&self, kind: NoiseKind, x: f32) -> f32 {
+        match kind {
+            NoiseKind::Value => self.value1(x),
+            NoiseKind::Perlin => self.perlin1(x),
+            NoiseKind::Simplex => self.simplex1(x),
+        }
+    }
+
+    /// Samples two dimensional noise.
+    pub fn sample2(&self, kind: NoiseKind, x: f32, y: f32) -> f32 {
+        match kind {
+            NoiseKind::Value => self.value2(x, y),
+            NoiseKind::Perlin => self.perlin2(x, y),
+            NoiseKind::Simplex => self.simplex2(x, y),
+        }
+    }
+
+    /// Samples three dimensional noise.
+    ///
+    /// Use the third dimension as time to animate two dimensional noise.
+    pub fn sample3(&self, kind: NoiseKind, x: f32, y: f32, z: f32) -> f32 {
+        match kind {
+            NoiseKind::Value => self.value3(x, y, z),
+            NoiseKind::Perlin => self.perlin3(x, y, z),
+            NoiseKind::Simplex => self.simplex3(x, y, z),
+        }
+    }
+
+    /// Layers octaves of one dimensional noise, for terrain lines.
+    pub fn fbm1(&self, kind: NoiseKind, fractal: Fractal, x: f32) -> f32 {
+        fbm(fractal, |frequency| self.sample1(kind, x * frequency))
+    }
+
+    /// Layers octaves of two dimensional noise, for height maps and clouds.
+    pub fn fbm2(&self, kind: NoiseKind, fractal: Fractal, x: f32, y: f32) -> f32 {
+        fbm(fractal, |frequency| {
+            self.sample2(kind, x * frequency, y * frequency)
+        })
+    }
+
+    /// Layers octaves of three dimensional noise.
+    pub fn fbm3(&self, kind: NoiseKind, fractal: Fractal, x: f32, y: f32, z: f32) -> f32 {
+        fbm(fractal, |frequency| {
+            self.sample3(kind, x * frequency, y * frequency, z * frequency)
+        })
+    }
+
+    /// Value noise in one dimension.
+    pub fn value1(&self, x: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let a = self.lattice(self.hash1(xi));
+        let b = self.lattice(self.hash1(xi + 1));
+        lerp(fade(xf), a, b)
+    }
+
+    /// Value noise in two dimensions.
+    pub fn value2(&self, x: f32, y: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let corner = |dx, dy| self.lattice(self.hash2(xi + dx, yi + dy));
+        let (u, v) = (fade(xf), fade(yf));
+        lerp(
+            v,
+            lerp(u, corner(0, 0), corner(1, 0)),
+            lerp(u, corner(0, 1), corner(1, 1)),
+        )
+    }
+
+    /// Value noise in three dimensions.
+    pub fn value3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (zi, zf) = split(z);
+        let corner = |dx, dy, dz| self.lattice(self.hash3(xi + dx, yi + dy, zi + dz));
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, corner(0, 0, 0), corner(1, 0, 0)),
+                lerp(u, corner(0, 1, 0), corner(1, 1, 0)),
+            ),
+            lerp(
+                v,
+                lerp(u, corner(0, 0, 1), corner(1, 0, 1)),
+                lerp(u, corner(0, 1, 1), corner(1, 1, 1)),
+            ),
+        )
+    }
+
+    /// Perlin noise in one dimension.
+    pub fn perlin1(&self, x: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let a = grad1(self.hash1(xi), xf);
+        let b = grad1(self.hash1(xi + 1), xf - 1.0);
+        // The gradients are at most 1, so the noise is at most 0.5.
+        2.0 * lerp(fade(xf), a, b)
+    }
+
+    /// Perlin noise in two dimensions.
+    pub fn perlin2(&self, x: f32, y: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let corner = |dx, dy| {
+            let hash = self.hash2(xi + dx, yi + dy);
+            grad2(hash, xf - dx as f32, yf - dy as f32)
+        };
+        let (u, v) = (fade(xf), fade(yf));
+        let noise = lerp(
+            v,
+            lerp(u, corner(0, 0), corner(1, 0)),
+            lerp(u, corner(0, 1), corner(1, 1)),
+        );
+        noise.clamp(-1.0, 1.0)
+    }
+
+    /// Perlin noise in three dimensions.
+    pub fn perlin3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (zi, zf) = split(z);
+        let corner = |dx, dy, dz| {
+            let hash = self.hash3(xi + dx, yi + dy, zi + dz);
+            grad3(hash, xf - dx as f32, yf - dy as f32, zf - dz as f32)
+        };
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+        let noise = lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, corner(0, 0, 0), corner(1, 0, 0)),
+                lerp(u, corner(0, 1, 0), corner(1, 1, 0)),
+            ),
+            lerp(
+                v,
+                lerp(u, corner(0, 0, 1), corner(1, 0, 1)),
+                lerp(u, corner(0, 1, 1), corner(1, 1, 1)),
+            ),
+        );
+        noise.clamp(-1.0, 1.0)
+    }
+
+    /// Simplex-style gradient noise in one dimension.
+    pub fn simplex1(&self, x: f32) -> f32 {
+        let (xi, x0) = split(x);
+        let x1 = x0 - 1.0;
+        let contribution = |hash: u8, d: f32| {
+            let t = 1.0 - d * d;
+            let t2 = t * t;
+            // Gradients from -8 to 8, except 0.
+            let mut gradient = 1.0 + (hash & 7) as f32;
+            if hash & 8 != 0 {
+                gradient = -gradient;
+            }
+            t2 * t2 * gradient * d
+        };
+        let n0 = contribution(self.hash1(xi), x0);
+        let n1 = contribution(self.hash1(xi + 1), x1);
+        // Scales the result to [-1, 1].
+        (0.395 * (n0 + n1)).clamp(-1.0, 1.0)
+    }
+
+    /// Simplex noise in two dimensions.
+    pub fn simplex2(&self, x: f32, y: f32) -> f32 {
+        // Skews the grid of squares into a grid of equilateral triangles and back.
+        const F2: f32 = 0.366_025_4; // (sqrt(3) - 1) / 2
+        const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+
+        let s = (x + y) * F2;
+        let i = libm::floorf(x + s) as i32;
+        let j = libm::floorf(y + s) as i32;
+        let t = (i + j) as f32 * G2;
+        let x0 = x - (i as f32 - t);
+        let y0 = y - (j as f32 - t);
+        // The middle corner of the triangle the point is in.
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        let corners = [
+            (0, 0, x0, y0),
+            (i1, j1, x0 - i1 as f32 + G2, y0 - j1 as f32 + G2),
+            (1, 1, x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2),
+        ];
+        let mut noise = 0.0;
+        for (di, dj, dx, dy) in corners {
+            let t = 0.5 - dx * dx - dy * dy;
+            if t > 0.0 {
+                let (gx, gy, _) = GRADIENTS[self.hash2(i + di, j + dj) as usize % 12];
+                let t2 = t * t;
+                noise += t2 * t2 * (gx * dx + gy * dy);
+            }
+        }
+        // Scales the result to [-1, 1].
+        (70.0 * noise).clamp(-1.0, 1.0)
+    }
+
+    /// Simplex noise in three dimensions.
+    pub fn simplex3(&self, x: f32, y: f32, z: f32) -> f32 {
+        const F3: f32 = 1.0 / 3.0;
+        const G3: f32 = 1.0 / 6.0;
+
+        let s = (x + y + z) * F3;
+        let i = libm::floorf(x + s) as i32;
+        let j = libm::floorf(y + s) as i32;
+        let k = libm::floorf(z + s) as i32;
+        let t = (i + j + k) as f32 * G3;
+        let x0 = x - (i as f32 - t);
+        let y0 = y - (j as f32 - t);
+        let z0 = z - (k as f32 - t);
+        // The two middle corners of the tetrahedron the point is in.
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let offset = |di: i32, dj: i32, dk: i32, g: f32| {
+            (
+                di,
+                dj,
+                dk,
+                x0 - di as f32 + g,
+                y0 - dj as f32 + g,
+                z0 - dk as f32 + g,
+            )
+        };
+        let corners = [
+            offset(0, 0, 0, 0.0),
+            offset(i1, j1, k1, G3),
+            offset(i2, j2, k2, 2.0 * G3),
+            offset(1, 1, 1, 3.0 * G3),
+        ];
+        let mut noise = 0.0;
+        for (di, dj, dk, dx, dy, dz) in corners {
+            let t = 0.6 - dx * dx - dy * dy - dz * dz;
+            if t > 0.0 {
+                let hash = self.hash3(i + di, j + dj, k + dk);
+                let (gx, gy, gz) = GRADIENTS[hash as usize % 12];
+                let t2 = t * t;
+                noise += t2 * t2 * (gx * dx + gy * dy + gz * dz);
+            }
+        }
+        // Scales the result to [-1, 1].
+        (32.0 * noise).clamp(-1.0, 1.0)
+    }
+
+    fn hash1(&self, x: i32) -> u8 {
+        self.perm[(x & 255) as usize]
+    }
+
+    fn hash2(&self, x: i32, y: i32) -> u8 {
+        self.perm[self.hash1(x) as usize + (y & 255) as usize]
+    }
+
+    fn hash3(&self, x: i32, y: i32, z: i32) -> u8 {
+        self.perm[self.hash2(x, y) as usize + (z & 255) as usize]
+    }
+
+    /// The value of value noise on a corner of the grid.
+    fn lattice(&self, hash: u8) -> f32 {
+        hash as f32 / 127.5 - 1.0
+    }
+}
+
+/// The gradients of Perlin's improved noise, the middles of the edges of a cube.
+const GRADIENTS: [(f32, f32, f32); 12] = [
+    (1.0, 1.0, 0.0),
+    (-1.0, 1.0, 0.0),
+    (1.0, -1.0, 0.0),
+    (-1.0, -1.0, 0.0),
+    (1.0, 0.0, 1.0),
+    (-1.0, 0.0, 1.0),
+    (1.0, 0.0, -1.0),
+    (-1.0, 0.0, -1.0),
+    (0.0, 1.0, 1.0),
+    (0.0, -1.0, 1.0),
+    (0.0, 1.0, -1.0),
+    (0.0, -1.0, -1.0),
+];
+
+/// Splits a coordinate into the grid cell and the position in the cell.
+fn split(x: f32) -> (i32, f32) {
+    let floor = libm::floorf(x);
+    (floor as i32, x - floor)
+}
+
+/// Perlin's smootherstep: 6t⁵ - 15t⁴ + 10t³.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32 {
+    a + t * (b - a)
+}
+
+fn grad1(hash: u8, x: f32) -> f32 {
+    if hash & 1 == 0 { x } else { -x }
+}
+
+fn grad2(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+fn grad3(hash: u8, x: f32, y: f32, z: f32) -> f32 {
+    let (gx, gy, gz) = GRADIENTS[hash as usize % 12];
+    gx * x + gy * y + gz * z
+}
+
+/// Sums octaves of noise, `sample` being given the frequency of each octave.
+fn fbm(fractal: Fractal, mut sample: impl FnMut(f32) -> f32) -> f32 {
+    let mut sum = 0.0;
+    let mut total_amplitude = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    for _ in 0..fractal.octaves {
+        sum += amplitude * sample(frequency);
+        total_amplitude += amplitude;
+        amplitude *= fractal.gain;
+        frequency *= fractal.lacunarity;
+    }
+    if total_amplitude == 0.0 {
+        return 0.0;
+    }
+    sum / total_amplitude
+}
+
+/// Maps values from 0 to 1 to colors, interpolating between stops.
+pub struct ColorRamp {
+    /// Sorted by position.
+    stops: Vec<(f32, Color)>,
+}
+
+impl ColorRamp {
+    /// Creates a ramp from `(position, color)` stops, positions going from 0 to 1.
+    pub fn new(stops: &[(f32, Color)]) -> Self {
+        let mut stops = stops.to_vec();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    /// From black to white.
+    pub fn grayscale() -> Self {
+        Self::new(&[(0.0, Color::BLACK), (1.0, Color::WHITE)])
+    }
+
+    /// Deep water, shore, grass, rock and snow, for height maps.
+    pub fn terrain() -> Self {
+        Self::new(&[
+            (0.0, Color::from_rgb(0x10, 0x30, 0x80)),
+            (0.45, Color::from_rgb(0x30, 0x70, 0xD0)),
+            (0.5, Color::from_rgb(0xE0, 0xD0, 0x90)),
+            (0.55, Color::from_rgb(0x40, 0xA0, 0x40)),
+            (0.75, Color::from_rgb(0x70, 0x60, 0x50)),
+            (0.9, Color::WHITE),
+        ])
+    }
+
+    /// Returns the color at `t`, clamped between the first and the last stop.
+    pub fn sample(&self, t: f32) -> Color {
+        let Some(&(first_position, first_color)) = self.stops.first() else {
+            return Color::BLACK;
+        };
+        if t <= first_position {
+            return first_color;
+        }
+        for pair in self.stops.windows(2) {
+            let ((start, from), (end, to)) = (pair[0], pair[1]);
+            if t <= end {
+                let span = end - start;
+                let local = if span > 0.0 { (t - start) / span } else { 1.0 };
+                return from.lerp(to, local);
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+}
+
+/// Fills a buffer with the colors of a noise field.
+///
+/// `field` is called for every pixel and must return a value between -1 and 1,
+/// which is mapped to the whole ramp.
+///
+/// A whole screen takes 150 KB of heap, so the screen is drawn in bands:
+///
+/// ```ignore
+/// let noise = Noise::new(42);
+/// // 2.5 KB of heap for 4 rows.
+/// let mut band = Buffer::new(320, 4, Color::BLACK);
+/// for top in (0..240).step_by(4) {
+///     render(&mut band, &ColorRamp::grayscale(), |x, y| {
+///         let (x, y) = (x as f32 / 64.0, (top + y) as f32 / 64.0);
+///         noise.fbm2(NoiseKind::Simplex, Fractal::default(), x, y)
+///     });
+///     band.push(0, top);
+/// }
+/// ```
+pub fn render(buffer: &mut Buffer, ramp: &ColorRamp, mut field: impl FnMut(u16, u16) -> f32) {
+    let width = buffer.width();
+    for (index, pixel) in buffer.pixels_mut().iter_mut().enumerate() {
+        let x = (index % width as usize) as u16;
+        let y = (index / width as usize) as u16;
+        let value = field(x, y);
+        *pixel = ramp.sample((value + 1.0) / 2.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [NoiseKind; 3] = [NoiseKind::Value, NoiseKind::Perlin, NoiseKind::Simplex];
+
+    /// Coordinates on and between the corners of the grid, negative ones included.
+    fn coordinates() -> impl Iterator<Item = f32> {
+        (-40..40).map(|i| i as f32 * 0.37)
+    }
+
+    #[test]
+    fn permutation() {
+        let noise = Noise::new(42);
+        let mut table = noise.perm[..256].to_vec();
+        assert_eq!(&noise.perm[..256], &noise.perm[256..]);
+        table.sort_unstable();
+        assert!(
+            table
+                .iter()
+                .enumerate()
+                .all(|(i, &value)| value as usize == i)
+        );
+    }
+
+    #[test]
+    fn same_seed_same_noise() {
+        let (a, b) = (Noise::new(42), Noise::new(42));
+        assert_eq!(a.perm, b.perm);
+        assert_ne!(a.perm, Noise::new(43).perm);
+        for kind in KINDS {
+            for x in coordinates() {
+                assert_eq!(a.sample2(kind, x, 0.5), b.sample2(kind, x, 0.5));
+            }
+        }
+    }
+
+    #[test]
+    fn in_range() {
+        let noise = Noise::new(7);
+        let fractal = Fractal::default();
+        let check = |value: f32| assert!((-1.0..=1.0).contains(&value), "{value}");
+        for kind in KINDS {
+            for x in coordinates() {
+                check(noise.sample1(kind, x));
+                check(noise.fbm1(kind, fractal, x));
+                for y in coordinates().step_by(7) {
+                    check(noise.sample2(kind, x, y));
+                    check(noise.fbm2(kind, fractal, x, y));
+                    check(noise.sample3(kind, x, y, x - y));
+                    check(noise.fbm3(kind, fractal, x, y, x - y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn zero_on_the_grid() {
+        // Gradient noise is 0 on the corners of the grid.
+        let noise = Noise::new(7);
+        for i in -5..5 {
+            let x = i as f32;
+            assert_eq!(noise.perlin1(x), 0.0);
+            assert_eq!(noise.perlin2(x, 3.0), 0.0);
+            assert_eq!(noise.perlin3(x, 3.0, -2.0), 0.0);
+        }
+    }
+}
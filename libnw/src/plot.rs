@@ -0,0 +1,676 @@
+extern crate alloc;
+
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+
+use crate::display::{self, CHAR_HEIGHT, CHAR_WIDTH, Color, Rect, eadk};
+use crate::keyboard::{self, Key};
+use crate::theme;
+
+/// How many times a segment of a curve can be cut in half to follow a steep slope.
+const MAX_SUBDIVISIONS: u8 = 6;
+/// The number of ticks aimed for on each axis.
+const TARGET_TICKS: f32 = 6.0;
+/// The most ticks drawn on an axis, in case the step is too small for the bounds.
+const MAX_TICKS: usize = 100;
+/// The size of the square drawn for each point of a point series, in pixels.
+const POINT_SIZE: u16 = 3;
+
+/// The part of the plane shown by a plot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub x_min: f32,
+    pub x_max: f32,
+    pub y_min: f32,
+    pub y_max: f32,
+}
+
+impl Bounds {
+    pub fn new(x_min: f32, x_max: f32, y_min: f32, y_max: f32) -> Self {
+        Self {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f32 {
+        self.y_max - self.y_min
+    }
+
+    /// Checks that the bounds have a finite, positive width and height.
+    pub fn is_valid(&self) -> bool {
+        let (width, height) = (self.width(), self.height());
+        width.is_finite() && height.is_finite() && width > 0.0 && height > 0.0
+    }
+}
+
+impl Default for Bounds {
+    /// From -10 to 10 horizontally, and the same scale vertically on the whole screen.
+    fn default() -> Self {
+        Self::new(-10.0, 10.0, -7.5, 7.5)
+    }
+}
+
+enum Data<'a> {
+    Function(Box<dyn Fn(f32) -> f32 + 'a>),
+    Points {
+        points: Vec<(f32, f32)>,
+        joined: bool,
+    },
+}
+
+/// A curve or a set of points drawn by a [`Plot`].
+pub struct Series<'a> {
+    data: Data<'a>,
+    color: Color,
+    label: String,
+}
+
+impl<'a> Series<'a> {
+    /// The curve y = f(x). `f` can return NaN or infinity where it is not defined.
+    pub fn function(f: impl Fn(f32) -> f32 + 'a, color: Color) -> Self {
+        Self {
+            data: Data::Function(Box::new(f)),
+            color,
+            label: String::new(),
+        }
+    }
+
+    /// A set of (x, y) points, drawn as small squares.
+    pub fn points(points: Vec<(f32, f32)>, color: Color) -> Self {
+        Self {
+            data: Data::Points {
+                points,
+                joined: false,
+            },
+            color,
+            label: String::new(),
+        }
+    }
+
+    /// Joins the points with lines, in the order they were given.
+    pub fn joined(mut self) -> Self {
+        if let Data::Points { joined, .. } = &mut self.data {
+            *joined = true;
+        }
+        self
+    }
+
+    /// Shows the name of the series in the legend.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = String::from(label);
+        self
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// What happened after a key has been given to a [`Plot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The plot doesn't use the key.
+    Ignored,
+    /// The view or the trace cursor changed, the plot should be redrawn.
+    Handled,
+    /// The user pressed `Back`.
+    Closed,
+}
+
+/// The cursor following a series.
+#[derive(Clone, Copy, Debug)]
+struct Trace {
+    series: usize,
+    /// The position on a function.
+    x: f32,
+    /// The position in a point series.
+    index: usize,
+}
+
+/// A graph of functions and point series, with axes, ticks and labels.
+///
+/// The arrows move the view, `+` and `-` zoom, `Ok` shows a cursor following
+/// the series (moved by the arrows, `Up` and `Down` switching series) and
+/// `Back` closes the plot.
+///
+/// ```ignore
+/// let mut plot = Plot::new(Rect::SCREEN)
+///     .with_series(Series::function(libm::sinf, Color::RED).with_label("sin"))
+///     .with_series(Series::function(|x| x * x / 4.0, Color::BLUE).with_label("x²/4"));
+/// plot.run();
+/// ```
+pub struct Plot<'a> {
+    rect: Rect,
+    bounds: Bounds,
+    series: Vec<Series<'a>>,
+    trace: Option<Trace>,
+}
+
+impl<'a> Plot<'a> {
+    /// Creates an empty plot drawn in `rect`, showing the default [`Bounds`].
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            rect,
+            bounds: Bounds::default(),
+            series: Vec::new(),
+            trace: None,
+        }
+    }
+
+    /// Shows another part of the plane. Invalid bounds are ignored.
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        self.set_bounds(bounds);
+        self
+    }
+
+    pub fn with_series(mut self, series: Series<'a>) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    pub fn add_series(&mut self, series: Series<'a>) {
+        self.series.push(series);
+    }
+
+    pub fn series(&self) -> &[Series<'a>] {
+        &self.series
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    /// Shows another part of the plane. Empty, reversed or infinite bounds
+    /// are ignored, see [`Bounds::is_valid`].
+    pub fn set_bounds(&mut self, bounds: Bounds) {
+        if bounds.is_valid() {
+            self.bounds = bounds;
+        }
+    }
+
+    /// Changes the vertical bounds to show all the values of the series
+    /// between the horizontal bounds, with a small margin.
+    pub fn fit_y(&mut self) {
+        let mut low = f32::INFINITY;
+        let mut high = f32::NEG_INFINITY;
+        let mut include = |y: f32| {
+            if y.is_finite() {
+                low = low.min(y);
+                high = high.max(y);
+            }
+        };
+        for series in &self.series {
+            match &series.data {
+                Data::Function(f) => {
+                    for column in 0..=self.rect.width {
+                        include(f(self.x_at(column as f32)));
+                    }
+                }
+                Data::Points { points, .. } => {
+                    for &(x, y) in points {
+                        if (self.bounds.x_min..=self.bounds.x_max).contains(&x) {
+                            include(y);
+                        }
+                    }
+                }
+            }
+        }
+        if low > high {
+            return;
+        }
+        let margin = if high > low { (high - low) / 10.0 } else { 1.0 };
+        self.set_bounds(Bounds::new(
+            self.bounds.x_min,
+            self.bounds.x_max,
+            low - margin,
+            high + margin,
+        ));
+    }
+
+    /// Zooms by `factor` (more than 1 to zoom in) around the trace cursor,
+    /// or the center of the view.
+    pub fn zoom(&mut self, factor: f32) {
+        if factor.is_nan() || factor <= 0.0 {
+            return;
+        }
+        let (center_x, center_y) = self
+            .trace_point()
+            .filter(|(_, y)| y.is_finite())
+            .unwrap_or((
+                (self.bounds.x_min + self.bounds.x_max) / 2.0,
+                (self.bounds.y_min + self.bounds.y_max) / 2.0,
+            ));
+        let half_width = self.bounds.width() / 2.0 / factor;
+        let half_height = self.bounds.height() / 2.0 / factor;
+        self.set_bounds(Bounds::new(
+            center_x - half_width,
+            center_x + half_width,
+            center_y - half_height,
+            center_y + half_height,
+        ));
+    }
+
+    /// Moves the view by a fraction of its size, positive `dy` going up.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let (dx, dy) = (dx * self.bounds.width(), dy * self.bounds.height());
+        self.set_bounds(Bounds::new(
+            self.bounds.x_min + dx,
+            self.bounds.x_max + dx,
+            self.bounds.y_min + dy,
+            self.bounds.y_max + dy,
+        ));
+    }
+
+    /// Shows or hides the trace cursor, starting on the first series at the center.
+    pub fn toggle_trace(&mut self) {
+        self.trace = match self.trace {
+            Some(_) => None,
+            None if self.series.is_empty() => None,
+            None => Some(Trace {
+                series: 0,
+                x: (self.bounds.x_min + self.bounds.x_max) / 2.0,
+                index: 0,
+            }),
+        };
+    }
+
+    /// The coordinates under the trace cursor, if it is shown.
+    pub fn trace_point(&self) -> Option<(f32, f32)> {
+        let trace = self.trace?;
+        match &self.series.get(trace.series)?.data {
+            Data::Function(f) => Some((trace.x, f(trace.x))),
+            Data::Points { points, .. } => points.get(trace.index).copied(),
+        }
+    }
+
+    /// Reacts to a keypress, see [`Plot`] for the keys.
+    pub fn handle_key(&mut self, key: Key) -> Response {
+        match key {
+            Key::Back => return Response::Closed,
+            Key::Ok | Key::Exe => self.toggle_trace(),
+            Key::Plus => self.zoom(2.0),
+            Key::Minus => self.zoom(0.5),
+            Key::Left | Key::Right if self.trace.is_some() => {
+                self.move_trace(if key == Key::Left { -1 } else { 1 });
+            }
+            Key::Up | Key::Down if self.trace.is_some() => {
+                let count = self.series.len();
+                if let Some(trace) = &mut self.trace {
+                    trace.series = if key == Key::Up {
+                        (trace.series + count - 1) % count
+                    } else {
+                        (trace.series + 1) % count
+                    };
+                }
+                self.follow_trace();
+            }
+            Key::Left => self.pan(-0.1, 0.0),
+            Key::Right => self.pan(0.1, 0.0),
+            Key::Up => self.pan(0.0, 0.1),
+            Key::Down => self.pan(0.0, -0.1),
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+
+    /// Draws the plot and handles the keys until the user presses `Back`.
+    pub fn run(&mut self) {
+        self.draw();
+        loop {
+            match self.handle_key(keyboard::wait_for_key()) {
+                Response::Ignored => (),
+                Response::Handled => self.draw(),
+                Response::Closed => return,
+            }
+        }
+    }
+
+    /// Draws the whole plot.
+    pub fn draw(&self) {
+        let theme = theme::current();
+        eadk::push_rect_uniform(self.rect, theme.background);
+        let x_step = tick_step(self.bounds.width(), TARGET_TICKS);
+        let y_step = tick_step(self.bounds.height(), TARGET_TICKS);
+        self.draw_grid(x_step, y_step);
+        for series in &self.series {
+            match &series.data {
+                Data::Function(f) => self.draw_function(f, series.color),
+                Data::Points { points, joined } => self.draw_points(points, *joined, series.color),
+            }
+        }
+        self.draw_labels(x_step, y_step);
+        self.draw_legend();
+        self.draw_trace();
+    }
+
+    fn move_trace(&mut self, direction: i32) {
+        let pixel = self.bounds.width() / self.rect.width.max(1) as f32;
+        let Some(trace) = &mut self.trace else {
+            return;
+        };
+        match &self.series[trace.series].data {
+            Data::Function(_) => trace.x += direction as f32 * pixel,
+            Data::Points { points, .. } => {
+                trace.index = trace
+                    .index
+                    .saturating_add_signed(direction as isize)
+                    .min(points.len().saturating_sub(1));
+            }
+        }
+        self.follow_trace();
+    }
+
+    /// Moves the view so the trace cursor stays visible.
+    fn follow_trace(&mut self) {
+        let Some((x, y)) = self.trace_point() else {
+            return;
+        };
+        // Each axis moves on its own, a point too far away on one axis
+        // doesn't keep the view from following it on the other.
+        let (width, height) = (self.bounds.width(), self.bounds.height());
+        if x < self.bounds.x_min || x > self.bounds.x_max {
+            self.set_bounds(Bounds {
+                x_min: x - width / 2.0,
+                x_max: x + width / 2.0,
+                ..self.bounds
+            });
+        }
+        if y < self.bounds.y_min || y > self.bounds.y_max {
+            self.set_bounds(Bounds {
+                y_min: y - height / 2.0,
+                y_max: y + height / 2.0,
+                ..self.bounds
+            });
+        }
+    }
+
+    /// The x coordinate at a horizontal position in pixels from the left of the plot.
+    fn x_at(&self, column: f32) -> f32 {
+        self.bounds.x_min + column / self.rect.width as f32 * self.bounds.width()
+    }
+
+    /// The position in pixels, from the top left corner of the plot, of a point of the plane.
+    fn to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.bounds.x_min) / self.bounds.width() * self.rect.width as f32,
+            (self.bounds.y_max - y) / self.bounds.height() * self.rect.height as f32,
+        )
+    }
+
+    fn draw_grid(&self, x_step: f32, y_step: f32) {
+        let theme = theme::current();
+        for x in ticks(self.bounds.x_min, self.bounds.x_max, x_step) {
+            self.vertical_line(self.to_screen(x, 0.0).0, theme.separator);
+        }
+        for y in ticks(self.bounds.y_min, self.bounds.y_max, y_step) {
+            self.horizontal_line(self.to_screen(0.0, y).1, theme.separator);
+        }
+        let (axis_x, axis_y) = self.to_screen(0.0, 0.0);
+        self.vertical_line(axis_x, theme.text);
+        self.horizontal_line(axis_y, theme.text);
+    }
+
+    fn vertical_line(&self, x: f32, color: Color) {
+        if x >= 0.0 && x < self.rect.width as f32 {
+            let rect = Rect::new(self.rect.x + x as u16, self.rect.y, 1, self.rect.height);
+            eadk::push_rect_uniform(rect, color);
+        }
+    }
+
+    fn horizontal_line(&self, y: f32, color: Color) {
+        if y >= 0.0 && y < self.rect.height as f32 {
+            let rect = Rect::new(self.rect.x, self.rect.y + y as u16, self.rect.width, 1);
+            eadk::push_rect_uniform(rect, color);
+        }
+    }
+
+    /// Draws the tick labels along the axes, or along the border when an axis is out of view.
+    fn draw_labels(&self, x_step: f32, y_step: f32) {
+        let (axis_x, axis_y) = self.to_screen(0.0, 0.0);
+        let bottom = self.rect.height.saturating_sub(CHAR_HEIGHT) as f32;
+        let label_y = (axis_y + 2.0).clamp(0.0, bottom) as u16;
+        for x in ticks(self.bounds.x_min, self.bounds.x_max, x_step) {
+            if x.abs() < x_step / 2.0 {
+                continue;
+            }
+            let text = format_number(x, x_step);
+            let width = text.len() as u16 * CHAR_WIDTH;
+            let center = self.to_screen(x, 0.0).0;
+            let left = center - width as f32 / 2.0;
+            if left >= 0.0 && left + width as f32 <= self.rect.width as f32 {
+                self.label(&text, left as u16, label_y);
+            }
+        }
+
+        let right = self.rect.width as f32;
+        for y in ticks(self.bounds.y_min, self.bounds.y_max, y_step) {
+            if y.abs() < y_step / 2.0 {
+                continue;
+            }
+            let text = format_number(y, y_step);
+            let width = text.len() as f32 * CHAR_WIDTH as f32;
+            if width > right {
+                continue;
+            }
+            let left = (axis_x - width - 2.0).clamp(0.0, right - width);
+            let top = self.to_screen(0.0, y).1 - CHAR_HEIGHT as f32 / 2.0;
+            if left >= 0.0 && top >= 0.0 && top <= bottom {
+                self.label(&text, left as u16, top as u16);
+            }
+        }
+    }
+
+    fn label(&self, text: &str, x: u16, y: u16) {
+        let theme = theme::current();
+        display::draw_string(
+            text,
+            self.rect.x + x,
+            self.rect.y + y,
+            false,
+            theme.secondary_text,
+            theme.background,
+        );
+    }
+
+    /// Draws the labels of the series in the top right corner, in their colors.
+    fn draw_legend(&self) {
+        let theme = theme::current();
+        let mut y = 2;
+        for series in self.series.iter().filter(|series| !series.label.is_empty()) {
+            let width = series.label.chars().count() as u16 * CHAR_WIDTH;
+            let x = self.rect.width.saturating_sub(width + 2);
+            display::draw_string(
+                &series.label,
+                self.rect.x + x,
+                self.rect.y + y,
+                false,
+                series.color,
+                theme.background,
+            );
+            y += CHAR_HEIGHT;
+        }
+    }
+
+    /// Draws a cross on the traced point, and its coordinates in the top left corner.
+    fn draw_trace(&self) {
+        let theme = theme::current();
+        let (Some(trace), Some((x, y))) = (self.trace, self.trace_point()) else {
+            return;
+        };
+        let color = self.series[trace.series].color;
+        let (sx, sy) = self.to_screen(x, y);
+        for (dx, dy) in [(-4.0, 0.0), (4.0, 0.0), (0.0, -4.0), (0.0, 4.0)] {
+            self.segment((sx, sy), (sx + dx, sy + dy), color);
+        }
+
+        let pixel = self.bounds.width() / self.rect.width.max(1) as f32;
+        let y_text = if y.is_finite() {
+            format_number(y, pixel)
+        } else {
+            String::from("undef")
+        };
+        let text = format!("x={} y={}", format_number(x, pixel), y_text);
+        display::draw_string(
+            &text,
+            self.rect.x + 2,
+            self.rect.y + 2,
+            false,
+            color,
+            theme.background,
+        );
+    }
+
+    /// Draws a curve, sampled once per pixel column and more often where it is steep.
+    fn draw_function(&self, f: &dyn Fn(f32) -> f32, color: Color) {
+        let mut previous: Option<(f32, f32)> = None;
+        for column in 0..=self.rect.width {
+            let x = self.x_at(column as f32);
+            let point = (x, f(x));
+            if let Some(previous) = previous {
+                self.curve(f, previous, point, color, 0);
+            }
+            previous = Some(point);
+        }
+    }
+
+    /// Draws the curve between two of its points, cutting the segment in half
+    /// while the two ends are far apart on the screen.
+    fn curve(&self, f: &dyn Fn(f32) -> f32, a: (f32, f32), b: (f32, f32), color: Color, depth: u8) {
+        // Nothing to draw if both ends are on the same side out of the view.
+        if (a.1 > self.bounds.y_max && b.1 > self.bounds.y_max)
+            || (a.1 < self.bounds.y_min && b.1 < self.bounds.y_min)
+        {
+            return;
+        }
+        let (start, end) = (self.to_screen(a.0, a.1), self.to_screen(b.0, b.1));
+        let finite = a.1.is_finite() && b.1.is_finite();
+        let gap = (end.1 - start.1).abs();
+        if finite && gap <= 1.0 {
+            self.segment(start, end, color);
+            return;
+        }
+        if depth == MAX_SUBDIVISIONS {
+            // Still a big jump in a tiny interval: a discontinuity, like tan at π/2.
+            if finite && gap < self.rect.height as f32 {
+                self.segment(start, end, color);
+            }
+            return;
+        }
+        let x = (a.0 + b.0) / 2.0;
+        let middle = (x, f(x));
+        self.curve(f, a, middle, color, depth + 1);
+        self.curve(f, middle, b, color, depth + 1);
+    }
+
+    fn draw_points(&self, points: &[(f32, f32)], joined: bool, color: Color) {
+        if joined {
+            for pair in points.windows(2) {
+                let start = self.to_screen(pair[0].0, pair[0].1);
+                let end = self.to_screen(pair[1].0, pair[1].1);
+                self.segment(start, end, color);
+            }
+        }
+        let half = (POINT_SIZE / 2) as f32;
+        for &(x, y) in points {
+            let (sx, sy) = self.to_screen(x, y);
+            let (left, top) = (libm::roundf(sx - half), libm::roundf(sy - half));
+            let size = POINT_SIZE as f32;
+            if left >= 0.0
+                && top >= 0.0
+                && left + size <= self.rect.width as f32
+                && top + size <= self.rect.height as f32
+            {
+                let rect = Rect::new_square(
+                    self.rect.x + left as u16,
+                    self.rect.y + top as u16,
+                    POINT_SIZE,
+                );
+                eadk::push_rect_uniform(rect, color);
+            }
+        }
+    }
+
+    /// Draws the part of a segment inside the plot, in pixels from its top left corner.
+    fn segment(&self, start: (f32, f32), end: (f32, f32), color: Color) {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let max_x = self.rect.width as f32 - 1.0;
+        let max_y = self.rect.height as f32 - 1.0;
+        // Liang-Barsky: keep the part of the segment inside the rect.
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+        for (p, q) in [
+            (-dx, start.0),
+            (dx, max_x - start.0),
+            (-dy, start.1),
+            (dy, max_y - start.1),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return;
+                }
+            } else if p < 0.0 {
+                t0 = t0.max(q / p);
+            } else {
+                t1 = t1.min(q / p);
+            }
+        }
+        if t0 > t1 {
+            return;
+        }
+        let point = |t: f32| {
+            let x = libm::roundf(start.0 + dx * t).clamp(0.0, max_x) as u16;
+            let y = libm::roundf(start.1 + dy * t).clamp(0.0, max_y) as u16;
+            (self.rect.x + x, self.rect.y + y)
+        };
+        let ((x0, y0), (x1, y1)) = (point(t0), point(t1));
+        display::draw_line(x0, y0, x1, y1, color);
+    }
+}
+
+/// A round distance between two ticks (1, 2 or 5 times a power of 10),
+/// giving about `target` ticks over `span`.
+pub(crate) fn tick_step(span: f32, target: f32) -> f32 {
+    let raw = span / target;
+    if raw <= 0.0 || !raw.is_finite() {
+        return 1.0;
+    }
+    let magnitude = libm::powf(10.0, libm::floorf(libm::log10f(raw)));
+    let normalized = raw / magnitude;
+    let nice = if normalized < 1.5 {
+        1.0
+    } else if normalized < 3.5 {
+        2.0
+    } else if normalized < 7.5 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// The multiples of `step` between `min` and `max`, at most [`MAX_TICKS`] of them.
+pub(crate) fn ticks(min: f32, max: f32, step: f32) -> impl Iterator<Item = f32> {
+    let first = libm::ceilf(min / step) as i64;
+    let last = libm::floorf(max / step) as i64;
+    (first..=last).take(MAX_TICKS).map(move |i| i as f32 * step)
+}
+
+/// Formats a number with as many decimals as needed to tell apart values `precision` apart.
+pub(crate) fn format_number(value: f32, precision: f32) -> String {
+    let decimals = (-libm::floorf(libm::log10f(precision))).clamp(0.0, 6.0) as usize;
+    let text = format!("{:.*}", decimals, value);
+    // Avoid "-0" and "-0.00".
+    if text.starts_with('-') && text[1..].chars().all(|c| c == '0' || c == '.') {
+        return String::from(&text[1..]);
+    }
+    text
+}
@@ -0,0 +1,256 @@
+use crate::backlight::{self, Brightness, BrightnessGuard};
+use crate::battery::{self, Charge};
+use crate::dialog::Toast;
+use crate::keyboard;
+use crate::time::{Duration, Instant};
+
+/// How long without any keypress before the screen is dimmed, by default.
+const DIM_AFTER: Duration = Duration::from_secs(30);
+/// How long without any keypress before the screen is turned off, by default.
+const SLEEP_AFTER: Duration = Duration::from_secs(120);
+/// The time between two steps of brightness while fading, by default.
+const FADE_STEP: Duration = Duration::from_millis(40);
+
+/// What the screen looks like, depending on the time since the last keypress.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerState {
+    /// At the brightness chosen by the user.
+    Active,
+    /// Fading down to, or at, the dimmed brightness.
+    Dimmed,
+    /// Fading down to, or at, a brightness of 0.
+    Asleep,
+}
+
+/// What changed during a call to [`PowerManager::update`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerEvent {
+    /// The screen started to dim.
+    Dimmed,
+    /// The screen started to turn off.
+    Asleep,
+    /// A key was pressed while the screen was dimmed or off, and its brightness
+    /// is back. Apps may want to ignore this keypress.
+    Woke,
+    /// The battery just became low while not charging.
+    LowBattery,
+}
+
+/// Dims the screen after some time without any keypress, and turns it off a
+/// bit later, to save the battery.
+///
+/// The manager has to be updated in the main loop. It reads the keyboard
+/// itself, apps which wait for keys elsewhere can call
+/// [`PowerManager::notify_activity`] too. During animations or videos, the
+/// screen can be kept on with [`PowerManager::set_inhibited`].
+///
+/// The brightness fades one [`Brightness`] level at a time. The brightness
+/// found when the manager is created is put back when it is dropped.
+/// A low battery is only reported with [`PowerEvent::LowBattery`], unless
+/// [`PowerManager::with_low_battery_warning`] is set.
+///
+/// ```ignore
+/// let mut power = PowerManager::new().with_dim_after(Duration::from_secs(10));
+/// loop {
+///     if power.update() == Some(PowerEvent::Woke) {
+///         continue;
+///     }
+///     // ...
+/// }
+/// ```
+pub struct PowerManager {
+    /// The brightness chosen by the user, put back on activity.
+    brightness: Brightness,
+    dim_brightness: Brightness,
+    dim_after: Duration,
+    sleep_after: Option<Duration>,
+    fade_step: Duration,
+    last_activity: Instant,
+    last_step: Instant,
+    state: PowerState,
+    inhibited: bool,
+    low_battery_warning: bool,
+    /// Whether the low battery was already reported, until the battery charges.
+    warned: bool,
+    toast: Option<Toast>,
+    /// Puts back the brightness found at creation when the manager is dropped.
+    _saved: BrightnessGuard,
+}
+
+impl PowerManager {
+    /// Creates a manager dimming the screen to a quarter of its current
+    /// brightness after 30 seconds, and turning it off after 2 minutes.
+    pub fn new() -> Self {
+        let brightness = backlight::get_level();
+        let now = Instant::now();
+        Self {
+            brightness,
+            dim_brightness: Brightness::saturating(brightness.level() / 4),
+            dim_after: DIM_AFTER,
+            sleep_after: Some(SLEEP_AFTER),
+            fade_step: FADE_STEP,
+            last_activity: now,
+            last_step: now,
+            state: PowerState::Active,
+            inhibited: false,
+            low_battery_warning: false,
+            warned: false,
+            toast: None,
+            _saved: BrightnessGuard::new(),
+        }
+    }
+
+    /// Sets the time without any keypress before the screen is dimmed.
+    pub fn with_dim_after(mut self, dim_after: Duration) -> Self {
+        self.dim_after = dim_after;
+        self
+    }
+
+    /// Sets the brightness of the dimmed screen, at most the brightness of the user.
+    pub fn with_dim_brightness(mut self, brightness: Brightness) -> Self {
+        self.dim_brightness = brightness.min(self.brightness);
+        self
+    }
+
+    /// Sets the time without any keypress before the screen is turned off,
+    /// or never with `None`.
+    pub fn with_sleep_after(mut self, sleep_after: Option<Duration>) -> Self {
+        self.sleep_after = sleep_after;
+        self
+    }
+
+    /// Sets the time between two steps of brightness while fading down.
+    pub fn with_fade_step(mut self, fade_step: Duration) -> Self {
+        self.fade_step = fade_step;
+        self
+    }
+
+    /// Whether a toast is shown when the battery becomes low, false by default.
+    ///
+    /// The toast saves the pixels under it on the heap, about 4 KB, more than
+    /// the heap of the template. [`PowerEvent::LowBattery`] is returned either way.
+    pub fn with_low_battery_warning(mut self, low_battery_warning: bool) -> Self {
+        self.low_battery_warning = low_battery_warning;
+        self
+    }
+
+    pub fn state(&self) -> PowerState {
+        self.state
+    }
+
+    /// The brightness put back on activity.
+    pub fn brightness(&self) -> Brightness {
+        self.brightness
+    }
+
+    /// Changes the brightness put back on activity, and applies it if the
+    /// screen isn't dimmed.
+    pub fn set_brightness(&mut self, brightness: Brightness) {
+        self.brightness = brightness;
+        self.dim_brightness = self.dim_brightness.min(self.brightness);
+        if self.state == PowerState::Active {
+            backlight::set_level(self.brightness);
+        }
+    }
+
+    pub fn is_inhibited(&self) -> bool {
+        self.inhibited
+    }
+
+    /// Keeps the screen at full brightness while inhibited, for animations
+    /// the user watches without pressing keys.
+    pub fn set_inhibited(&mut self, inhibited: bool) {
+        self.inhibited = inhibited;
+        if inhibited {
+            self.notify_activity();
+        }
+    }
+
+    /// Counts as a keypress: puts the brightness back and restarts the timers.
+    /// Returns whether the screen was dimmed or off.
+    pub fn notify_activity(&mut self) -> bool {
+        self.last_activity = Instant::now();
+        if self.state == PowerState::Active {
+            return false;
+        }
+        self.state = PowerState::Active;
+        backlight::set_level(self.brightness);
+        true
+    }
+
+    /// Reads the keyboard and the battery, and fades the brightness.
+    ///
+    /// Returns what changed, if anything. When several things changed at
+    /// once, waking up is reported first.
+    pub fn update(&mut self) -> Option<PowerEvent> {
+        if let Some(toast) = &mut self.toast
+            && !toast.update()
+        {
+            self.toast = None;
+        }
+
+        if self.inhibited || keyboard::scan().0 != 0 {
+            if self.notify_activity() {
+                return Some(PowerEvent::Woke);
+            }
+        } else if let Some(event) = self.fade() {
+            return Some(event);
+        }
+        self.check_battery()
+    }
+
+    /// Moves to the state matching the idle time, and steps the brightness
+    /// toward its target.
+    fn fade(&mut self) -> Option<PowerEvent> {
+        let idle = self.last_activity.elapsed();
+        let mut event = None;
+        if self
+            .sleep_after
+            .is_some_and(|sleep_after| idle >= sleep_after)
+        {
+            if self.state != PowerState::Asleep {
+                self.state = PowerState::Asleep;
+                event = Some(PowerEvent::Asleep);
+            }
+        } else if idle >= self.dim_after && self.state == PowerState::Active {
+            self.state = PowerState::Dimmed;
+            event = Some(PowerEvent::Dimmed);
+        }
+
+        let target = match self.state {
+            PowerState::Active => return event,
+            PowerState::Dimmed => self.dim_brightness,
+            PowerState::Asleep => Brightness::OFF,
+        };
+        let current = backlight::get_level();
+        if current > target && self.last_step.elapsed() >= self.fade_step {
+            backlight::set_level(current.dimmer());
+            self.last_step = Instant::now();
+        }
+        event
+    }
+
+    /// Warns once when the battery becomes low, and again after a charge.
+    fn check_battery(&mut self) -> Option<PowerEvent> {
+        if battery::is_charging() || battery::get_charge() > Charge::Low {
+            self.warned = false;
+            return None;
+        }
+        if self.warned {
+            return None;
+        }
+        self.warned = true;
+        if self.low_battery_warning {
+            let mut toast = Toast::new("Low battery").with_duration(Duration::from_secs(3));
+            toast.show();
+            self.toast = Some(toast);
+        }
+        Some(PowerEvent::LowBattery)
+    }
+}
+
+impl Default for PowerManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
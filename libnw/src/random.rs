@@ -1,12 +1,18 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::f32::consts::TAU;
+use core::ops::Range;
+
 use crate::display::{Color, Rect, SCREEN_HEIGHT, SCREEN_WIDTH};
 
 pub use eadk::random;
 
 /// Returns a random unsigned integer in [a, b[.
+///
+/// If the range is empty (`b <= a`), returns `a`.
 pub fn randuint(a: u32, b: u32) -> u32 {
-    let range = b - a;
-    let random_number = eadk::random() % range;
-    random_number + a
+    Entropy.range(a..b)
 }
 
 /// Returns a random color
@@ -24,6 +30,312 @@ pub fn random_rect() -> Rect {
     Rect::new(x, y, width, height)
 }
 
+/// A source of random numbers.
+///
+/// Everything is built on [`RandomSource::next_u32`], so the same code works with
+/// the hardware generator ([`Entropy`]) and a seeded one ([`Rng`]).
+pub trait RandomSource {
+    /// Returns a random u32, all values being equally likely.
+    fn next_u32(&mut self) -> u32;
+
+    /// Returns a random u64, all values being equally likely.
+    fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        let low = self.next_u32() as u64;
+        (high << 32) | low
+    }
+
+    /// Returns a random integer in the range, without the bias of a modulo.
+    ///
+    /// If the range is empty, returns its start.
+    fn range(&mut self, range: Range<u32>) -> u32 {
+        if range.end <= range.start {
+            return range.start;
+        }
+        range.start + self.below(range.end - range.start)
+    }
+
+    /// Returns a random signed integer in the range, without the bias of a modulo.
+    ///
+    /// If the range is empty, returns its start.
+    fn range_i32(&mut self, range: Range<i32>) -> i32 {
+        if range.end <= range.start {
+            return range.start;
+        }
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add_unsigned(self.below(span))
+    }
+
+    /// Returns a random integer in [0, n[, or 0 if `n` is 0.
+    fn below(&mut self, n: u32) -> u32 {
+        if n == 0 {
+            return 0;
+        }
+        // Lemire's method: multiply instead of dividing, and reject the few
+        // values that would make some results more likely than others.
+        let mut product = self.next_u32() as u64 * n as u64;
+        if (product as u32) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (product as u32) < threshold {
+                product = self.next_u32() as u64 * n as u64;
+            }
+        }
+        (product >> 32) as u32
+    }
+
+    /// Returns a random float in [0, 1[.
+    fn f32(&mut self) -> f32 {
+        // A f32 has 24 bits of precision.
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Returns a random float in the range.
+    fn f32_range(&mut self, range: Range<f32>) -> f32 {
+        range.start + self.f32() * (range.end - range.start)
+    }
+
+    /// Returns true with the given probability, from 0 to 1.
+    fn bool(&mut self, probability: f32) -> bool {
+        self.f32() < probability
+    }
+
+    /// Shuffles a slice, all orders being equally likely.
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        // Fisher-Yates
+        for i in (1..slice.len()).rev() {
+            let j = self.below(i as u32 + 1) as usize;
+            slice.swap(i, j);
+        }
+    }
+
+    /// Returns a random element of the slice, or `None` if it is empty.
+    fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            return None;
+        }
+        slice.get(self.below(slice.len() as u32) as usize)
+    }
+
+    /// Returns a random index, each index being as likely as its weight.
+    ///
+    /// Returns `None` if all the weights are 0.
+    fn weighted_index(&mut self, weights: &[u32]) -> Option<usize> {
+        let total = weights.iter().map(|weight| *weight as u64).sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+        let mut target = if total <= u32::MAX as u64 {
+            self.below(total as u32) as u64
+        } else {
+            // Very large totals are rare enough to accept the tiny bias.
+            self.next_u64() % total
+        };
+        weights.iter().position(|weight| {
+            let found = target < *weight as u64;
+            target = target.saturating_sub(*weight as u64);
+            found
+        })
+    }
+
+    /// Returns a random element of the slice, each element being as likely as its weight.
+    ///
+    /// Returns `None` if the slice is empty or all the weights are 0.
+    fn choose_weighted<'a, T>(
+        &mut self,
+        slice: &'a [T],
+        weight: impl Fn(&T) -> u32,
+    ) -> Option<&'a T> {
+        let weights: Vec<u32> = slice.iter().map(weight).collect();
+        self.weighted_index(&weights).map(|index| &slice[index])
+    }
+
+    /// Returns `amount` distinct indices in [0, n[, in no particular order.
+    ///
+    /// If `amount` is larger than `n`, all the indices are returned.
+    fn sample(&mut self, n: usize, amount: usize) -> Vec<usize> {
+        let amount = amount.min(n);
+        let mut chosen = Vec::with_capacity(amount);
+        // Floyd's algorithm, which doesn't need to allocate `n` indices.
+        for j in n - amount..n {
+            let t = self.below(j as u32 + 1) as usize;
+            chosen.push(if chosen.contains(&t) { j } else { t });
+        }
+        chosen
+    }
+
+    /// Returns `amount` distinct elements of the slice, in no particular order.
+    ///
+    /// If `amount` is larger than the slice, all the elements are returned.
+    fn choose_multiple<'a, T>(&mut self, slice: &'a [T], amount: usize) -> Vec<&'a T> {
+        self.sample(slice.len(), amount)
+            .into_iter()
+            .map(|index| &slice[index])
+            .collect()
+    }
+
+    /// Returns a float following a normal (gaussian) distribution.
+    fn normal(&mut self, mean: f32, std_dev: f32) -> f32 {
+        // Box-Muller transform, 1 - f32() is in ]0, 1] so the log is finite.
+        let u = 1.0 - self.f32();
+        let v = self.f32();
+        mean + std_dev * libm::sqrtf(-2.0 * libm::logf(u)) * libm::cosf(TAU * v)
+    }
+
+    /// Returns a float following an exponential distribution with the given rate,
+    /// for example the time between two events happening `rate` times per second.
+    fn exponential(&mut self, rate: f32) -> f32 {
+        -libm::logf(1.0 - self.f32()) / rate
+    }
+
+    /// Returns a random pixel of the rect.
+    ///
+    /// Returns the origin of the rect if it is empty.
+    fn point_in_rect(&mut self, rect: Rect) -> (u16, u16) {
+        let x = self.below(rect.width as u32) as u16;
+        let y = self.below(rect.height as u32) as u16;
+        (rect.x + x, rect.y + y)
+    }
+
+    /// Returns a random point in the disc, all points being equally likely.
+    fn point_in_circle(&mut self, center: (f32, f32), radius: f32) -> (f32, f32) {
+        // Without the square root, points would gather at the center.
+        let distance = radius * libm::sqrtf(self.f32());
+        let angle = TAU * self.f32();
+        (
+            center.0 + distance * libm::cosf(angle),
+            center.1 + distance * libm::sinf(angle),
+        )
+    }
+}
+
+/// The hardware random number generator of the calculator.
+///
+/// It can't be seeded, use [`Rng`] for reproducible sequences.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Entropy;
+
+impl RandomSource for Entropy {
+    fn next_u32(&mut self) -> u32 {
+        eadk::random()
+    }
+}
+
+/// A fast seedable random number generator (xoshiro128++).
+///
+/// The same seed always gives the same sequence, on the calculator as on a
+/// computer, so levels generated from a seed can be shared and tested.
+///
+/// It is not suitable for cryptography.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: [u32; 4],
+}
+
+impl Rng {
+    /// Creates a generator from a seed.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        // Spread the seed with splitmix64, as recommended by the authors of xoshiro:
+        // similar seeds give unrelated sequences and the state is never all zeros.
+        let mut seed = seed;
+        let mut next = || {
+            seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        let (a, b) = (next(), next());
+        Self {
+            state: [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32],
+        }
+    }
+
+    /// Creates a generator seeded by the hardware random number generator.
+    pub fn from_entropy() -> Self {
+        Self::seed_from_u64(Entropy.next_u64())
+    }
+
+    /// Creates a generator from its raw state, as returned by [`Rng::state`].
+    ///
+    /// Returns `None` for the all zeros state, which only produces zeros.
+    pub fn from_state(state: [u32; 4]) -> Option<Self> {
+        if state == [0; 4] {
+            return None;
+        }
+        Some(Self { state })
+    }
+
+    /// The raw state, to save the generator and resume the sequence later.
+    pub fn state(&self) -> [u32; 4] {
+        self.state
+    }
+}
+
+impl RandomSource for Rng {
+    fn next_u32(&mut self) -> u32 {
+        let s = &mut self.state;
+        let result = s[0].wrapping_add(s[3]).rotate_left(7).wrapping_add(s[0]);
+        let t = s[1] << 9;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(11);
+        result
+    }
+}
+
+#[cfg(feature = "rand_core")]
+mod rand_core_impls {
+    use super::{Entropy, RandomSource, Rng};
+
+    impl rand_core::RngCore for Rng {
+        fn next_u32(&mut self) -> u32 {
+            RandomSource::next_u32(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            RandomSource::next_u64(self)
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dst);
+        }
+    }
+
+    impl rand_core::SeedableRng for Rng {
+        type Seed = [u8; 16];
+
+        /// The all zeros seed is replaced by another one, see [`Rng::from_state`].
+        fn from_seed(seed: Self::Seed) -> Self {
+            let mut state = [0; 4];
+            for (word, bytes) in state.iter_mut().zip(seed.chunks_exact(4)) {
+                *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            }
+            Rng::from_state(state).unwrap_or_else(|| Rng::seed_from_u64(0))
+        }
+
+        fn seed_from_u64(seed: u64) -> Self {
+            Rng::seed_from_u64(seed)
+        }
+    }
+
+    impl rand_core::RngCore for Entropy {
+        fn next_u32(&mut self) -> u32 {
+            RandomSource::next_u32(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            RandomSource::next_u64(self)
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dst);
+        }
+    }
+}
+
 /// Interface with the raw `eadk` C api.
 ///
 /// If you don't know what you are doing, use the safe rust implementations.
@@ -37,3 +349,67 @@ pub mod eadk {
         fn eadk_random() -> u32;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xoshiro128plusplus_reference() {
+        // The first outputs of the reference implementation for this state.
+        let mut rng = Rng::from_state([1, 2, 3, 4]).unwrap();
+        let expected = [
+            641, 1573767, 3222811527, 3517856514, 836907274, 4247214768, 3867114732, 1355841295,
+            495546011, 621204420,
+        ];
+        for value in expected {
+            assert_eq!(rng.next_u32(), value);
+        }
+    }
+
+    #[test]
+    fn seed_from_u64() {
+        // splitmix64 gives 0xE220A8397B1DCDAF and 0x6E789E6AA1B965F4 for 0.
+        let mut rng = Rng::seed_from_u64(0);
+        assert_eq!(
+            rng.state(),
+            [2065550767, 3793791033, 2713282036, 1853398634]
+        );
+        let expected = [1179900579, 1938959192, 3089844957, 3657088315, 1015453891];
+        for value in expected {
+            assert_eq!(rng.next_u32(), value);
+        }
+        assert_eq!(Rng::from_state([0; 4]), None);
+    }
+
+    #[test]
+    fn below() {
+        let mut rng = Rng::seed_from_u64(42);
+        assert_eq!(rng.below(0), 0);
+        assert_eq!(rng.below(1), 0);
+        for n in [2, 3, 7, 1000, u32::MAX] {
+            for _ in 0..1000 {
+                assert!(rng.below(n) < n);
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn range() {
+        let mut rng = Rng::seed_from_u64(42);
+        assert_eq!(rng.range(5..5), 5);
+        assert_eq!(rng.range(10..3), 10);
+        assert_eq!(rng.range(u32::MAX - 1..u32::MAX), u32::MAX - 1);
+        for _ in 0..1000 {
+            assert!((3..10).contains(&rng.range(3..10)));
+        }
+
+        assert_eq!(rng.range_i32(-4..-4), -4);
+        assert_eq!(rng.range_i32(5..-5), 5);
+        for _ in 0..1000 {
+            assert!((-5..5).contains(&rng.range_i32(-5..5)));
+            assert!(rng.range_i32(i32::MIN..i32::MAX) != i32::MAX);
+        }
+    }
+}
@@ -0,0 +1,783 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::display::{Color, Rect, eadk};
+use crate::math::{Mat3, Vec3};
+use crate::noise::ColorRamp;
+
+/// How the triangles of the meshes are drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shading {
+    /// Only the edges, in the color of the triangles and without lighting.
+    Wireframe,
+    /// One shade per triangle, lit according to its orientation.
+    Flat,
+    /// The lighting is computed at the vertices and blended across the triangles,
+    /// for smooth surfaces.
+    Gouraud,
+}
+
+/// A 3D model made of triangles.
+///
+/// The coordinates are left-handed: x goes right, y goes up and z goes away from the
+/// viewer. The vertices of a triangle are listed counter-clockwise as seen from the
+/// outside, the other side is hidden unless the mesh is double sided.
+pub struct Mesh {
+    vertices: Vec<Vec3>,
+    triangles: Vec<[u16; 3]>,
+    colors: Vec<Color>,
+    /// One per triangle, pointing outside.
+    face_normals: Vec<Vec3>,
+    /// One per vertex, the average of the normals of the triangles around it.
+    vertex_normals: Vec<Vec3>,
+    /// The two triangles on each side of an edge (the same one twice on a border).
+    edges: Vec<Edge>,
+    double_sided: bool,
+}
+
+struct Edge {
+    vertices: [u16; 2],
+    triangles: [usize; 2],
+}
+
+impl Mesh {
+    /// Creates a mesh from vertices and triangles made of the indices of 3 vertices,
+    /// all the triangles having the same color.
+    ///
+    /// Triangles using a vertex that doesn't exist are dropped.
+    pub fn new(vertices: Vec<Vec3>, mut triangles: Vec<[u16; 3]>, color: Color) -> Self {
+        triangles.retain(|triangle| triangle.iter().all(|&i| (i as usize) < vertices.len()));
+
+        let face_normals: Vec<Vec3> = triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let [a, b, c] = [a, b, c].map(|i| vertices[i as usize]);
+                (c - a).cross(b - a).normalize()
+            })
+            .collect();
+
+        let mut vertex_normals = alloc::vec![Vec3::ZERO; vertices.len()];
+        for (triangle, normal) in triangles.iter().zip(&face_normals) {
+            for &i in triangle {
+                vertex_normals[i as usize] += *normal;
+            }
+        }
+        for normal in &mut vertex_normals {
+            *normal = normal.normalize();
+        }
+
+        // Sorting the edges puts the two sides of a shared edge next to each other.
+        let mut sides: Vec<([u16; 2], usize)> = triangles
+            .iter()
+            .enumerate()
+            .flat_map(|(t, &[a, b, c])| {
+                [(a, b), (b, c), (c, a)].map(|(u, v)| ([u.min(v), u.max(v)], t))
+            })
+            .collect();
+        sides.sort_unstable();
+        let mut edges: Vec<Edge> = Vec::with_capacity(sides.len() / 2);
+        for (vertices, triangle) in sides {
+            match edges.last_mut() {
+                Some(edge)
+                    if edge.vertices == vertices && edge.triangles[0] == edge.triangles[1] =>
+                {
+                    edge.triangles[1] = triangle;
+                }
+                _ => edges.push(Edge {
+                    vertices,
+                    triangles: [triangle; 2],
+                }),
+            }
+        }
+        // The diagonals of flat faces are not real edges of the shape.
+        edges.retain(|edge| {
+            let [first, second] = edge.triangles;
+            first == second || face_normals[first].dot(face_normals[second]) < 0.999
+        });
+
+        Self {
+            colors: alloc::vec![color; triangles.len()],
+            vertices,
+            triangles,
+            face_normals,
+            vertex_normals,
+            edges,
+            double_sided: false,
+        }
+    }
+
+    /// A cube from -1 to 1 on every axis.
+    pub fn cube(color: Color) -> Self {
+        let vertices = (0..8)
+            .map(|i| {
+                let coordinate = |bit: u8| if i & bit != 0 { 1.0 } else { -1.0 };
+                Vec3::new(coordinate(1), coordinate(2), coordinate(4))
+            })
+            .collect();
+        let faces = [
+            [0, 1, 3, 2],
+            [4, 6, 7, 5],
+            [0, 4, 5, 1],
+            [2, 3, 7, 6],
+            [0, 2, 6, 4],
+            [1, 5, 7, 3],
+        ];
+        let triangles = faces
+            .iter()
+            .flat_map(|&[a, b, c, d]| [[a, b, c], [a, c, d]])
+            .collect();
+        Self::new(vertices, triangles, color)
+    }
+
+    /// A regular tetrahedron, its vertices at a distance of √3 from the center.
+    pub fn tetrahedron(color: Color) -> Self {
+        let vertices = Vec::from([
+            Vec3::new(1.0, 1.0, 1.0),
+            Vec3::new(1.0, -1.0, -1.0),
+            Vec3::new(-1.0, 1.0, -1.0),
+            Vec3::new(-1.0, -1.0, 1.0),
+        ]);
+        let triangles = Vec::from([[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]]);
+        Self::new(vertices, triangles, color)
+    }
+
+    /// A regular octahedron, its vertices at a distance of 1 from the center.
+    pub fn octahedron(color: Color) -> Self {
+        let vertices = Vec::from([
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 0.0, -1.0),
+        ]);
+        let triangles = Vec::from([
+            [2, 0, 4],
+            [2, 4, 1],
+            [2, 1, 5],
+            [2, 5, 0],
+            [3, 4, 0],
+            [3, 1, 4],
+            [3, 5, 1],
+            [3, 0, 5],
+        ]);
+        Self::new(vertices, triangles, color)
+    }
+
+    /// The surface y = f(x, z), sampled on a grid of `steps` × `steps` squares,
+    /// colored by height with the ramp. The mesh is double sided.
+    ///
+    /// `steps` is clamped between 1 and 180 so the vertices can be indexed.
+    pub fn surface(
+        f: impl Fn(f32, f32) -> f32,
+        x: Range<f32>,
+        z: Range<f32>,
+        steps: u16,
+        ramp: &ColorRamp,
+    ) -> Self {
+        let steps = steps.clamp(1, 180);
+        let side = steps + 1;
+        let mut vertices = Vec::with_capacity(side as usize * side as usize);
+        for row in 0..side {
+            let vz = z.start + (z.end - z.start) * row as f32 / steps as f32;
+            for column in 0..side {
+                let vx = x.start + (x.end - x.start) * column as f32 / steps as f32;
+                vertices.push(Vec3::new(vx, f(vx, vz), vz));
+            }
+        }
+
+        let mut triangles = Vec::with_capacity(steps as usize * steps as usize * 2);
+        for row in 0..steps {
+            for column in 0..steps {
+                let a = row * side + column;
+                let (b, c, d) = (a + 1, a + side + 1, a + side);
+                triangles.push([a, d, c]);
+                triangles.push([a, c, b]);
+            }
+        }
+
+        // Non finite heights (outside the domain of f) are left out of the range.
+        let (low, high) = vertices
+            .iter()
+            .map(|vertex| vertex.y)
+            .filter(|y| y.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), y| {
+                (low.min(y), high.max(y))
+            });
+        let span = if high > low { high - low } else { 1.0 };
+        triangles.retain(|triangle: &[u16; 3]| {
+            triangle.iter().all(|&i| vertices[i as usize].y.is_finite())
+        });
+
+        let mut mesh = Self::new(vertices, triangles, Color::WHITE);
+        for (color, triangle) in mesh.colors.iter_mut().zip(&mesh.triangles) {
+            let height = triangle
+                .iter()
+                .map(|&i| mesh.vertices[i as usize].y)
+                .sum::<f32>()
+                / 3.0;
+            *color = ramp.sample((height - low) / span);
+        }
+        mesh.double_sided = true;
+        mesh
+    }
+
+    /// Makes both sides of the triangles visible, for open surfaces.
+    pub fn with_double_sided(mut self, double_sided: bool) -> Self {
+        self.double_sided = double_sided;
+        self
+    }
+
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
+    pub fn triangles(&self) -> &[[u16; 3]] {
+        &self.triangles
+    }
+
+    /// The color of each triangle, to paint the faces one by one.
+    pub fn colors_mut(&mut self) -> &mut [Color] {
+        &mut self.colors
+    }
+}
+
+/// A mesh placed in the world: scaled, then rotated, then moved.
+#[derive(Clone, Copy)]
+pub struct Instance<'a> {
+    pub mesh: &'a Mesh,
+    pub rotation: Mat3,
+    pub position: Vec3,
+    pub scale: f32,
+}
+
+impl<'a> Instance<'a> {
+    /// The mesh as is, at the origin of the world.
+    pub fn new(mesh: &'a Mesh) -> Self {
+        Self {
+            mesh,
+            rotation: Mat3::IDENTITY,
+            position: Vec3::ZERO,
+            scale: 1.0,
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: Mat3) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    fn place(&self, vertex: Vec3) -> Vec3 {
+        self.rotation * (vertex * self.scale) + self.position
+    }
+}
+
+/// Where the world is seen from.
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    pub position: Vec3,
+    /// The rows are the right, up and forward directions of the camera.
+    pub orientation: Mat3,
+    /// The vertical field of view in radians.
+    pub fov: f32,
+    /// Nothing closer than this distance is drawn.
+    pub near: f32,
+}
+
+impl Camera {
+    /// A camera looking towards +z, with a field of view of 60°.
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            orientation: Mat3::IDENTITY,
+            fov: core::f32::consts::FRAC_PI_3,
+            near: 0.1,
+        }
+    }
+
+    /// A camera at `distance` from `target` and looking at it, turned by `yaw`
+    /// radians around the y axis and raised by `pitch` radians.
+    ///
+    /// With both angles at 0, the camera is on the -z side of the target.
+    pub fn orbit(target: Vec3, distance: f32, yaw: f32, pitch: f32) -> Self {
+        let offset = Vec3::new(
+            libm::cosf(pitch) * libm::sinf(yaw),
+            libm::sinf(pitch),
+            -libm::cosf(pitch) * libm::cosf(yaw),
+        );
+        Self::new(target + offset * distance).looking_at(target)
+    }
+
+    pub fn with_fov(mut self, fov: f32) -> Self {
+        self.fov = fov;
+        self
+    }
+
+    pub fn looking_at(mut self, target: Vec3) -> Self {
+        self.look_at(target);
+        self
+    }
+
+    /// Turns the camera towards `target`, keeping the y axis up.
+    ///
+    /// Does nothing if the target is the position of the camera.
+    pub fn look_at(&mut self, target: Vec3) {
+        let forward = (target - self.position).normalize();
+        if forward == Vec3::ZERO {
+            return;
+        }
+        let mut right = Vec3::new(0.0, 1.0, 0.0).cross(forward).normalize();
+        if right == Vec3::ZERO {
+            // Looking straight up or down, any right direction will do.
+            right = Vec3::new(1.0, 0.0, 0.0);
+        }
+        let up = forward.cross(right);
+        self.orientation = Mat3::from_rows([
+            [right.x, right.y, right.z],
+            [up.x, up.y, up.z],
+            [forward.x, forward.y, forward.z],
+        ]);
+    }
+
+    fn view(&self, point: Vec3) -> Vec3 {
+        self.orientation * (point - self.position)
+    }
+}
+
+/// A directional light, like the sun.
+#[derive(Clone, Copy, Debug)]
+pub struct Light {
+    /// Towards the light.
+    pub direction: Vec3,
+    /// The brightness of the faces turned away from the light, from 0 to 1.
+    pub ambient: f32,
+}
+
+impl Light {
+    pub fn new(direction: Vec3, ambient: f32) -> Self {
+        Self {
+            direction: direction.normalize(),
+            ambient: ambient.clamp(0.0, 1.0),
+        }
+    }
+
+    /// The brightness of a surface, from 0 to 1.
+    fn intensity(&self, normal: Vec3) -> f32 {
+        self.ambient + (1.0 - self.ambient) * normal.dot(self.direction).max(0.0)
+    }
+}
+
+impl Default for Light {
+    /// Above, on the left and behind a default camera.
+    fn default() -> Self {
+        Self::new(Vec3::new(-0.4, 0.8, -0.5), 0.25)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    position: Vec3,
+    light: f32,
+}
+
+#[derive(Clone, Copy)]
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    /// 1 / depth, which unlike the depth can be interpolated on the screen.
+    inverse_depth: f32,
+    light: f32,
+}
+
+struct ScreenTriangle {
+    vertices: [ScreenVertex; 3],
+    color: Color,
+    top: f32,
+    bottom: f32,
+}
+
+struct ScreenLine {
+    from: (f32, f32),
+    to: (f32, f32),
+    color: Color,
+}
+
+/// Draws meshes in a rect of the screen.
+///
+/// The rect is drawn in horizontal bands, so only one band of colors and depths
+/// is kept in memory: 6 bytes per pixel of the band.
+///
+/// ```ignore
+/// let cube = Mesh::cube(Color::from_rgb(0xFF, 0xB7, 0x34));
+/// let mut renderer = Renderer::new(Rect::SCREEN).with_shading(Shading::Flat);
+/// let camera = Camera::orbit(Vec3::ZERO, 5.0, 0.6, 0.4);
+/// let mut angle = 0.0;
+/// loop {
+///     angle += 0.05;
+///     renderer.render(&camera, &[Instance::new(&cube).with_rotation(Mat3::rotation_y(angle))]);
+/// }
+/// ```
+pub struct Renderer {
+    viewport: Rect,
+    band_height: u16,
+    background: Color,
+    shading: Shading,
+    light: Light,
+    colors: Vec<Color>,
+    depths: Vec<f32>,
+    triangles: Vec<ScreenTriangle>,
+    lines: Vec<ScreenLine>,
+}
+
+impl Renderer {
+    /// Creates a renderer drawing in `viewport`, 16 rows at a time.
+    pub fn new(viewport: Rect) -> Self {
+        Self {
+            viewport,
+            band_height: 0,
+            background: Color::BLACK,
+            shading: Shading::Gouraud,
+            light: Light::default(),
+            colors: Vec::new(),
+            depths: Vec::new(),
+            triangles: Vec::new(),
+            lines: Vec::new(),
+        }
+        .with_band_height(16)
+    }
+
+    /// Changes the number of rows drawn at a time: more rows are a bit faster,
+    /// but take more memory.
+    pub fn with_band_height(mut self, band_height: u16) -> Self {
+        self.band_height = band_height.clamp(1, self.viewport.height.max(1));
+        let len = self.viewport.width as usize * self.band_height as usize;
+        self.colors = alloc::vec![self.background; len];
+        self.depths = alloc::vec![0.0; len];
+        self
+    }
+
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with_shading(mut self, shading: Shading) -> Self {
+        self.shading = shading;
+        self
+    }
+
+    pub fn with_light(mut self, light: Light) -> Self {
+        self.light = light;
+        self
+    }
+
+    pub fn shading(&self) -> Shading {
+        self.shading
+    }
+
+    pub fn set_shading(&mut self, shading: Shading) {
+        self.shading = shading;
+    }
+
+    pub fn set_light(&mut self, light: Light) {
+        self.light = light;
+    }
+
+    /// Draws the meshes seen from the camera, over the whole viewport.
+    pub fn render(&mut self, camera: &Camera, instances: &[Instance]) {
+        self.triangles.clear();
+        self.lines.clear();
+        for instance in instances {
+            self.prepare(camera, instance);
+        }
+
+        let width = self.viewport.width as usize;
+        let mut band_top = 0;
+        while band_top < self.viewport.height {
+            let rows = self.band_height.min(self.viewport.height - band_top);
+            let len = width * rows as usize;
+            self.colors[..len].fill(self.background);
+            self.depths[..len].fill(0.0);
+            let band = band_top as f32..(band_top + rows) as f32;
+
+            for triangle in &self.triangles {
+                if triangle.bottom >= band.start && triangle.top < band.end {
+                    fill_triangle(
+                        &mut self.colors,
+                        &mut self.depths,
+                        width,
+                        band_top,
+                        rows,
+                        triangle,
+                        self.shading,
+                    );
+                }
+            }
+            for line in &self.lines {
+                draw_line(&mut self.colors, width, band_top, rows, line);
+            }
+
+            eadk::push_rect(
+                Rect::new(
+                    self.viewport.x,
+                    self.viewport.y + band_top,
+                    self.viewport.width,
+                    rows,
+                ),
+                &self.colors[..len],
+            );
+            band_top += rows;
+        }
+    }
+
+    /// Transforms, culls, lights, clips and projects the triangles or edges of an instance.
+    fn prepare(&mut self, camera: &Camera, instance: &Instance) {
+        let mesh = instance.mesh;
+        let vertices: Vec<Vec3> = mesh
+            .vertices
+            .iter()
+            .map(|&vertex| camera.view(instance.place(vertex)))
+            .collect();
+        let facing: Vec<bool> = mesh
+            .triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let [a, b, c] = [a, b, c].map(|i| vertices[i as usize]);
+                (c - a).cross(b - a).dot(a) < 0.0
+            })
+            .collect();
+
+        let focal = self.viewport.height as f32 / 2.0 / libm::tanf(camera.fov / 2.0);
+        let project = |vertex: ClipVertex| ScreenVertex {
+            x: self.viewport.width as f32 / 2.0 + focal * vertex.position.x / vertex.position.z,
+            y: self.viewport.height as f32 / 2.0 - focal * vertex.position.y / vertex.position.z,
+            inverse_depth: 1.0 / vertex.position.z,
+            light: vertex.light,
+        };
+
+        if self.shading == Shading::Wireframe {
+            for edge in &mesh.edges {
+                let [first, second] = edge.triangles;
+                if !mesh.double_sided && !facing[first] && !facing[second] {
+                    continue;
+                }
+                let [from, to] = edge.vertices.map(|i| ClipVertex {
+                    position: vertices[i as usize],
+                    light: 1.0,
+                });
+                let Some((from, to)) = clip_segment(from, to, camera.near) else {
+                    continue;
+                };
+                let (from, to) = (project(from), project(to));
+                self.lines.push(ScreenLine {
+                    from: (from.x, from.y),
+                    to: (to.x, to.y),
+                    color: mesh.colors[first],
+                });
+            }
+            return;
+        }
+
+        let world_normal = |normal: Vec3| (instance.rotation * normal).normalize();
+        for (index, triangle) in mesh.triangles.iter().enumerate() {
+            if !facing[index] && !mesh.double_sided {
+                continue;
+            }
+            // The back of a double sided triangle is lit as if it faced the other way.
+            let side = if facing[index] { 1.0 } else { -1.0 };
+            let flat_light = self
+                .light
+                .intensity(world_normal(mesh.face_normals[index]) * side);
+            let corners = triangle.map(|i| ClipVertex {
+                position: vertices[i as usize],
+                light: match self.shading {
+                    Shading::Gouraud => self
+                        .light
+                        .intensity(world_normal(mesh.vertex_normals[i as usize]) * side),
+                    _ => flat_light,
+                },
+            });
+
+            let (polygon, count) = clip_triangle(corners, camera.near);
+            let color = match self.shading {
+                Shading::Gouraud => mesh.colors[index],
+                _ => shade(mesh.colors[index], flat_light),
+            };
+            for i in 1..count.saturating_sub(1) {
+                let vertices = [polygon[0], polygon[i], polygon[i + 1]].map(project);
+                let ys = vertices.map(|vertex| vertex.y);
+                self.triangles.push(ScreenTriangle {
+                    vertices,
+                    color,
+                    top: ys[0].min(ys[1]).min(ys[2]),
+                    bottom: ys[0].max(ys[1]).max(ys[2]),
+                });
+            }
+        }
+    }
+}
+
+/// Darkens a color, `light` going from 0 (black) to 1 (the color itself).
+fn shade(color: Color, light: f32) -> Color {
+    Color::BLACK.lerp(color, light)
+}
+
+fn lerp_vertex(a: ClipVertex, b: ClipVertex, t: f32) -> ClipVertex {
+    ClipVertex {
+        position: a.position.lerp(b.position, t),
+        light: a.light + (b.light - a.light) * t,
+    }
+}
+
+/// Cuts the part of a triangle closer than `near`, which can leave a quadrilateral.
+///
+/// Returns the vertices of the polygon and their number.
+fn clip_triangle(corners: [ClipVertex; 3], near: f32) -> ([ClipVertex; 4], usize) {
+    let mut polygon = [corners[0]; 4];
+    let mut count = 0;
+    for i in 0..3 {
+        let (current, next) = (corners[i], corners[(i + 1) % 3]);
+        let (current_in, next_in) = (current.position.z >= near, next.position.z >= near);
+        if current_in {
+            polygon[count] = current;
+            count += 1;
+        }
+        if current_in != next_in {
+            let t = (near - current.position.z) / (next.position.z - current.position.z);
+            polygon[count] = lerp_vertex(current, next, t);
+            count += 1;
+        }
+    }
+    (polygon, count)
+}
+
+/// Cuts the part of a segment closer than `near`, returns `None` if nothing is left.
+fn clip_segment(from: ClipVertex, to: ClipVertex, near: f32) -> Option<(ClipVertex, ClipVertex)> {
+    match (from.position.z >= near, to.position.z >= near) {
+        (true, true) => Some((from, to)),
+        (false, false) => None,
+        (from_in, _) => {
+            let t = (near - from.position.z) / (to.position.z - from.position.z);
+            let cut = lerp_vertex(from, to, t);
+            Some(if from_in { (from, cut) } else { (cut, to) })
+        }
+    }
+}
+
+/// Fills the pixels of a band whose centers are inside the triangle and closer than
+/// what was already drawn there.
+fn fill_triangle(
+    colors: &mut [Color],
+    depths: &mut [f32],
+    width: usize,
+    band_top: u16,
+    rows: u16,
+    triangle: &ScreenTriangle,
+    shading: Shading,
+) {
+    let [v0, v1, v2] = triangle.vertices;
+    // Twice the signed area, its sign depends on the winding on screen.
+    let area = (v1.x - v0.x) * (v2.y - v0.y) - (v1.y - v0.y) * (v2.x - v0.x);
+    if area.abs() < 1e-6 {
+        return;
+    }
+
+    let left = libm::floorf(v0.x.min(v1.x).min(v2.x)).max(0.0) as usize;
+    let right = libm::ceilf(v0.x.max(v1.x).max(v2.x)).min(width as f32) as usize;
+    let top = libm::floorf(triangle.top).max(band_top as f32) as u16;
+    let bottom = libm::ceilf(triangle.bottom).min((band_top + rows) as f32) as u16;
+    if left >= right {
+        return;
+    }
+
+    // The barycentric weight of a vertex is the area of the triangle made by the
+    // pixel and the opposite edge, it changes by a constant step from one pixel to the next.
+    let weight = |a: ScreenVertex, b: ScreenVertex, x: f32, y: f32| {
+        ((b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)) / area
+    };
+    let step = |a: ScreenVertex, b: ScreenVertex| -(b.y - a.y) / area;
+    let (step0, step1, step2) = (step(v1, v2), step(v2, v0), step(v0, v1));
+
+    for y in top..bottom {
+        let (x, py) = (left as f32 + 0.5, y as f32 + 0.5);
+        let (mut w0, mut w1, mut w2) = (
+            weight(v1, v2, x, py),
+            weight(v2, v0, x, py),
+            weight(v0, v1, x, py),
+        );
+        let row = (y - band_top) as usize * width;
+        for x in left..right {
+            if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
+                let depth = w0 * v0.inverse_depth + w1 * v1.inverse_depth + w2 * v2.inverse_depth;
+                let index = row + x;
+                if depth > depths[index] {
+                    depths[index] = depth;
+                    colors[index] = match shading {
+                        Shading::Gouraud => shade(
+                            triangle.color,
+                            w0 * v0.light + w1 * v1.light + w2 * v2.light,
+                        ),
+                        _ => triangle.color,
+                    };
+                }
+            }
+            w0 += step0;
+            w1 += step1;
+            w2 += step2;
+        }
+    }
+}
+
+/// Draws the part of a line inside a band.
+fn draw_line(colors: &mut [Color], width: usize, band_top: u16, rows: u16, line: &ScreenLine) {
+    let (mut x0, mut y0) = line.from;
+    let (mut x1, mut y1) = line.to;
+    // Liang-Barsky: keep the part of the line inside the band.
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    let bounds = [
+        (-dx, x0),
+        (dx, width as f32 - 1.0 - x0),
+        (-dy, y0 - band_top as f32),
+        (dy, (band_top + rows) as f32 - 1.0 - y0),
+    ];
+    for (p, q) in bounds {
+        if p == 0.0 {
+            if q < 0.0 {
+                return;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    if t0 > t1 {
+        return;
+    }
+    (x0, y0, x1, y1) = (x0 + dx * t0, y0 + dy * t0, x0 + dx * t1, y0 + dy * t1);
+
+    let steps = libm::ceilf((x1 - x0).abs().max((y1 - y0).abs())).max(1.0) as usize;
+    for i in 0..=steps {
+        let t = i as f32 / steps as f32;
+        let x = libm::roundf(x0 + (x1 - x0) * t) as usize;
+        let y = libm::roundf(y0 + (y1 - y0) * t) as usize;
+        let row = y.saturating_sub(band_top as usize).min(rows as usize - 1);
+        colors[row * width + x.min(width - 1)] = line.color;
+    }
+}
@@ -0,0 +1,147 @@
+extern crate alloc;
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::keyboard::{self, Key};
+use crate::time::{Duration, Instant};
+
+/// How to handle the end of the main app.
+pub enum ExitBehaviour {
+    Exit,
+    Hang,
+    Restart,
+}
+
+/// What to do with the scene stack after a scene handled an event or updated.
+pub enum Transition {
+    /// Stay on the current scene.
+    None,
+    /// Show a new scene on top of the current one (a pause menu).
+    Push(Box<dyn Scene>),
+    /// Go back to the scene below. Popping the last scene exits the app.
+    Pop,
+    /// Swap the current scene for a new one (from the title screen to the game).
+    Replace(Box<dyn Scene>),
+    /// Stop the whole stack.
+    Quit(ExitBehaviour),
+}
+
+/// A screen of the app: a title screen, the gameplay, a pause menu...
+///
+/// Only the scene on top of the stack receives events and is updated and drawn.
+pub trait Scene {
+    /// Called when the scene is pushed on the stack.
+    fn enter(&mut self) {}
+
+    /// Called when the scene is on top of the stack again,
+    /// after the scene above it was popped.
+    fn resume(&mut self) {}
+
+    /// Called for every keypress.
+    fn handle_event(&mut self, key: Key) -> Transition {
+        let _ = key;
+        Transition::None
+    }
+
+    /// Called once per frame, `dt` being the time elapsed since the last one, in seconds.
+    fn update(&mut self, dt: f32) -> Transition {
+        let _ = dt;
+        Transition::None
+    }
+
+    /// Called once per frame, after the update.
+    fn draw(&mut self);
+
+    /// Called when the scene is popped or replaced.
+    fn exit(&mut self) {}
+}
+
+/// Runs a stack of scenes until the last one is popped or one of them quits.
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    frame_duration: Duration,
+}
+
+impl SceneStack {
+    /// Creates a stack starting on `root`, running at 30 frames per second.
+    pub fn new(root: impl Scene + 'static) -> Self {
+        Self {
+            scenes: Vec::from([Box::new(root) as Box<dyn Scene>]),
+            frame_duration: Duration::from_secs(1) / 30,
+        }
+    }
+
+    /// Changes the number of frames per second.
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.frame_duration = Duration::from_secs(1) / fps.max(1);
+        self
+    }
+
+    /// Runs the scenes, returns what to do once they are done.
+    pub fn run(mut self) -> ExitBehaviour {
+        if let Some(root) = self.scenes.last_mut() {
+            root.enter();
+        }
+        let mut last_frame = Instant::now();
+        loop {
+            // Handle the events until the next frame is due.
+            let next_frame = last_frame + self.frame_duration;
+            loop {
+                let timeout = next_frame.saturating_duration_since(Instant::now());
+                let Some(key) = keyboard::wait_for_input(timeout.as_millis() as i32) else {
+                    break;
+                };
+                let transition = self.top().handle_event(key);
+                if let Some(behaviour) = self.apply(transition) {
+                    return behaviour;
+                }
+            }
+
+            let now = Instant::now();
+            let dt = now.saturating_duration_since(last_frame).as_secs_f32();
+            last_frame = now;
+            let transition = self.top().update(dt);
+            if let Some(behaviour) = self.apply(transition) {
+                return behaviour;
+            }
+            self.top().draw();
+        }
+    }
+
+    fn top(&mut self) -> &mut dyn Scene {
+        // The stack is never empty while running, see `apply`.
+        self.scenes.last_mut().unwrap().as_mut()
+    }
+
+    /// Changes the stack, returns the exit behaviour if the stack is done.
+    fn apply(&mut self, transition: Transition) -> Option<ExitBehaviour> {
+        match transition {
+            Transition::None => (),
+            Transition::Push(mut scene) => {
+                scene.enter();
+                self.scenes.push(scene);
+            }
+            Transition::Pop => {
+                self.top().exit();
+                self.scenes.pop();
+                match self.scenes.last_mut() {
+                    Some(scene) => scene.resume(),
+                    None => return Some(ExitBehaviour::Exit),
+                }
+            }
+            Transition::Replace(mut scene) => {
+                self.top().exit();
+                self.scenes.pop();
+                scene.enter();
+                self.scenes.push(scene);
+            }
+            Transition::Quit(behaviour) => {
+                while let Some(mut scene) = self.scenes.pop() {
+                    scene.exit();
+                }
+                return Some(behaviour);
+            }
+        }
+        None
+    }
+}
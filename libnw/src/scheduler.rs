@@ -0,0 +1,220 @@
+extern crate alloc;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::ops::ControlFlow;
+
+use crate::keyboard::{self, Key};
+use crate::time::{Duration, Instant};
+
+/// Identifies a timer, to cancel it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(u32);
+
+/// The shortest period of a repeating timer.
+const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+struct Timer<S> {
+    id: TimerId,
+    deadline: Instant,
+    /// The period of repeating timers.
+    period: Option<Duration>,
+    callback: Box<dyn FnMut(&mut S)>,
+}
+
+/// One-shot and repeating timers, calling back with the state of the app `S`.
+///
+/// The timers don't run by themselves: call [`Timers::fire_due`] regularly,
+/// or let a [`Scheduler`] do it.
+pub struct Timers<S> {
+    /// Sorted by deadline, the next one first.
+    timers: Vec<Timer<S>>,
+    next_id: u32,
+}
+
+impl<S> Default for Timers<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Timers<S> {
+    /// Creates an empty set of timers.
+    pub fn new() -> Self {
+        Self {
+            timers: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Calls `callback` once, after `delay`.
+    pub fn after(&mut self, delay: Duration, callback: impl FnMut(&mut S) + 'static) -> TimerId {
+        self.schedule(delay, None, Box::new(callback))
+    }
+
+    /// Calls `callback` every `period`, starting one period from now.
+    ///
+    /// The period is at least one millisecond, the resolution of [`Instant`].
+    pub fn every(&mut self, period: Duration, callback: impl FnMut(&mut S) + 'static) -> TimerId {
+        let period = period.max(MIN_PERIOD);
+        self.schedule(period, Some(period), Box::new(callback))
+    }
+
+    /// Stops a timer, returns false if it already fired or was cancelled.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != count
+    }
+
+    /// Checks if the timer will fire again.
+    pub fn is_active(&self, id: TimerId) -> bool {
+        self.timers.iter().any(|timer| timer.id == id)
+    }
+
+    /// Checks if there is no timer left.
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// When the next timer will fire, if any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.first().map(|timer| timer.deadline)
+    }
+
+    /// The time left before the next timer fires, if any.
+    pub fn time_until_next(&self) -> Option<Duration> {
+        self.next_deadline()
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Calls the callbacks of the timers whose deadline passed, each at most once.
+    ///
+    /// Returns the number of callbacks called.
+    pub fn fire_due(&mut self, state: &mut S) -> usize {
+        let now = Instant::now();
+        let due = self.timers.partition_point(|timer| timer.deadline <= now);
+        let fired: Vec<Timer<S>> = self.timers.drain(..due).collect();
+        let count = fired.len();
+        for mut timer in fired {
+            (timer.callback)(state);
+            if let Some(period) = timer.period {
+                // Don't fire several times in a row after a long block,
+                // but don't drift either.
+                timer.deadline += period;
+                if timer.deadline <= now {
+                    timer.deadline = now + period;
+                }
+                self.insert(timer);
+            }
+        }
+        count
+    }
+
+    fn schedule(
+        &mut self,
+        delay: Duration,
+        period: Option<Duration>,
+        callback: Box<dyn FnMut(&mut S)>,
+    ) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        self.insert(Timer {
+            id,
+            deadline: Instant::now() + delay,
+            period,
+            callback,
+        });
+        id
+    }
+
+    fn insert(&mut self, timer: Timer<S>) {
+        let index = self
+            .timers
+            .partition_point(|other| other.deadline <= timer.deadline);
+        self.timers.insert(index, timer);
+    }
+}
+
+/// A task run by a [`Scheduler`], called once per loop until it breaks.
+pub type Task<S> = Box<dyn FnMut(&mut S) -> ControlFlow<()>>;
+
+/// Runs timers and tasks while waiting for keypresses.
+///
+/// When nothing is left to do, the scheduler blocks on the keyboard until
+/// the next timer deadline, so the calculator doesn't spin while idle.
+///
+/// ```ignore
+/// let mut scheduler = Scheduler::new();
+/// scheduler.timers_mut().every(Duration::from_millis(500), |field: &mut Field| {
+///     field.cursor_visible = !field.cursor_visible;
+///     field.draw();
+/// });
+/// scheduler.run(&mut field, |field, key, _timers| field.handle_key(key));
+/// ```
+pub struct Scheduler<S> {
+    timers: Timers<S>,
+    tasks: Vec<Task<S>>,
+}
+
+impl<S> Default for Scheduler<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Scheduler<S> {
+    /// Creates a scheduler without any timer or task.
+    pub fn new() -> Self {
+        Self {
+            timers: Timers::new(),
+            tasks: Vec::new(),
+        }
+    }
+
+    /// The timers run by the scheduler.
+    pub fn timers(&self) -> &Timers<S> {
+        &self.timers
+    }
+
+    /// The timers run by the scheduler, to add or cancel some.
+    pub fn timers_mut(&mut self) -> &mut Timers<S> {
+        &mut self.timers
+    }
+
+    /// Adds a task, called once per loop until it breaks.
+    ///
+    /// Tasks must return quickly: the keyboard is only read between them.
+    pub fn spawn(&mut self, task: impl FnMut(&mut S) -> ControlFlow<()> + 'static) {
+        self.tasks.push(Box::new(task));
+    }
+
+    /// Runs the timers, the tasks and `on_key` for every keypress,
+    /// until `on_key` breaks.
+    pub fn run(
+        &mut self,
+        state: &mut S,
+        mut on_key: impl FnMut(&mut S, Key, &mut Timers<S>) -> ControlFlow<()>,
+    ) {
+        loop {
+            self.timers.fire_due(state);
+            self.tasks.retain_mut(|task| task(state).is_continue());
+
+            if let Some(key) = keyboard::wait_for_input(self.timeout_ms())
+                && on_key(state, key, &mut self.timers).is_break()
+            {
+                return;
+            }
+        }
+    }
+
+    /// How long to wait for a keypress before there is work to do.
+    fn timeout_ms(&self) -> i32 {
+        if !self.tasks.is_empty() {
+            return 0;
+        }
+        match self.timers.time_until_next() {
+            Some(duration) => duration.as_millis().min(i32::MAX as u128) as i32,
+            None => i32::MAX,
+        }
+    }
+}
@@ -0,0 +1,252 @@
+extern crate alloc;
+
+use alloc::{format, string::String};
+
+use crate::battery::{self, Charge};
+use crate::display::{self, Color, Rect, eadk};
+use crate::theme::{self, Theme};
+use crate::time::Instant;
+use crate::ui::{self, TITLE_BAR_HEIGHT, Widget};
+use crate::usb;
+
+/// The size of the battery gauge, without its tip, in pixels.
+const BATTERY_WIDTH: u16 = 18;
+const BATTERY_HEIGHT: u16 = 10;
+/// The size of the tip on the right of the battery gauge, in pixels.
+const TIP_WIDTH: u16 = 2;
+const TIP_HEIGHT: u16 = 4;
+
+const MILLIS_PER_MINUTE: u64 = 60 * 1000;
+const MINUTES_PER_DAY: u64 = 24 * 60;
+
+/// The lightning bolt drawn over the battery while it charges, one byte per
+/// row, the leftmost pixel being the highest bit.
+const BOLT: [u8; 6] = [0x0C, 0x18, 0x3C, 0x18, 0x30, 0x20];
+/// The USB plug shown while the calculator is plugged.
+const USB_PLUG: [u8; 9] = [0x18, 0x3C, 0x18, 0x99, 0x99, 0x5A, 0x3C, 0x18, 0x18];
+/// The width of the icons, in pixels.
+const ICON_WIDTH: u16 = 8;
+
+/// The values shown by the status bar, to know what changed since the last drawing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Status {
+    charge: Charge,
+    charging: bool,
+    plugged: bool,
+    /// The minutes since midnight, if the clock is shown.
+    minutes: Option<u64>,
+}
+
+/// The header of the firmware apps: a title, the battery, the USB plug and a clock.
+///
+/// [`StatusBar::update`] reads the battery and the clock again and redraws
+/// only the parts which changed, so it can be called in every frame:
+///
+/// ```ignore
+/// let mut status = StatusBar::new("My app").with_clock(true);
+/// status.set_time(12, 30);
+/// status.draw(false);
+/// loop {
+///     status.update();
+///     // ...
+/// }
+/// ```
+pub struct StatusBar {
+    rect: Rect,
+    title: String,
+    clock: bool,
+    /// When the status bar was created, the clock counts from there.
+    start: Instant,
+    /// Added to the time since `start` to get the time of the clock, in milliseconds.
+    clock_offset: u64,
+    status: Status,
+}
+
+impl StatusBar {
+    /// Creates a status bar at the top of the screen, without a clock.
+    pub fn new(title: &str) -> Self {
+        let mut status_bar = Self {
+            rect: Rect::new(0, 0, display::SCREEN_WIDTH, TITLE_BAR_HEIGHT),
+            title: String::from(title),
+            clock: false,
+            start: Instant::now(),
+            clock_offset: 0,
+            status: Status {
+                charge: Charge::Full,
+                charging: false,
+                plugged: false,
+                minutes: None,
+            },
+        };
+        status_bar.status = status_bar.read_status();
+        status_bar
+    }
+
+    /// Shows a clock on the left, starting from 00:00 when the status bar was
+    /// created until [`StatusBar::set_time`] is called.
+    pub fn with_clock(mut self, clock: bool) -> Self {
+        self.clock = clock;
+        self.status = self.read_status();
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = String::from(title);
+    }
+
+    /// Sets the time shown by the clock, which then keeps counting.
+    pub fn set_time(&mut self, hours: u8, minutes: u8) {
+        let target = (hours as u64 % 24 * 60 + minutes as u64 % 60) * MILLIS_PER_MINUTE;
+        let now = self.start.elapsed().as_millis() as u64 % (MINUTES_PER_DAY * MILLIS_PER_MINUTE);
+        self.clock_offset = (target + MINUTES_PER_DAY * MILLIS_PER_MINUTE - now)
+            % (MINUTES_PER_DAY * MILLIS_PER_MINUTE);
+        self.status = self.read_status();
+    }
+
+    /// Reads the battery, the USB and the clock, and redraws what changed.
+    /// Returns whether something was drawn.
+    pub fn update(&mut self) -> bool {
+        let status = self.read_status();
+        if status == self.status {
+            return false;
+        }
+        let previous = core::mem::replace(&mut self.status, status);
+        let theme = theme::current();
+        if status.charge != previous.charge || status.charging != previous.charging {
+            self.draw_battery(&theme);
+        }
+        if status.plugged != previous.plugged {
+            self.draw_usb(&theme);
+        }
+        if status.minutes != previous.minutes {
+            self.draw_clock(&theme);
+        }
+        true
+    }
+
+    fn read_status(&self) -> Status {
+        Status {
+            charge: battery::get_charge(),
+            charging: battery::is_charging(),
+            plugged: usb::is_plugged(),
+            minutes: self.clock.then(|| {
+                (self.start.elapsed().as_millis() as u64 + self.clock_offset) / MILLIS_PER_MINUTE
+                    % MINUTES_PER_DAY
+            }),
+        }
+    }
+
+    /// The top of an icon of the given height, centered vertically.
+    fn icon_y(&self, height: u16) -> u16 {
+        self.rect.y + self.rect.height.saturating_sub(height) / 2
+    }
+
+    /// The left of the battery gauge, next to the right border.
+    fn battery_x(&self, theme: &Theme) -> u16 {
+        (self.rect.x + self.rect.width).saturating_sub(theme.padding + BATTERY_WIDTH + TIP_WIDTH)
+    }
+
+    fn draw_battery(&self, theme: &Theme) {
+        let x = self.battery_x(theme);
+        let y = self.icon_y(BATTERY_HEIGHT);
+        let body = Rect::new(x, y, BATTERY_WIDTH, BATTERY_HEIGHT);
+        eadk::push_rect_uniform(body, theme.accent);
+        ui::draw_border(body, theme.on_accent);
+        let tip = Rect::new(
+            x + BATTERY_WIDTH,
+            self.icon_y(TIP_HEIGHT),
+            TIP_WIDTH,
+            TIP_HEIGHT,
+        );
+        eadk::push_rect_uniform(tip, theme.on_accent);
+
+        // Inside the border, a sliver of red when empty, then thirds.
+        let inside_width = BATTERY_WIDTH - 4;
+        let (filled, color) = match self.status.charge {
+            Charge::Empty => (2, theme.error),
+            charge => (inside_width * charge.level() as u16 / 3, theme.on_accent),
+        };
+        let inside = Rect::new(x + 2, y + 2, filled, BATTERY_HEIGHT - 4);
+        eadk::push_rect_uniform(inside, color);
+
+        if self.status.charging {
+            // The bolt stands out of the gauge, whether it is over the charge or not.
+            let bolt_x = x + (BATTERY_WIDTH - ICON_WIDTH) / 2;
+            let bolt_y = self.icon_y(BOLT.len() as u16);
+            draw_icon(&BOLT, bolt_x, bolt_y, |px| {
+                if px < x + 2 + filled {
+                    theme.accent
+                } else {
+                    theme.on_accent
+                }
+            });
+        }
+    }
+
+    fn draw_usb(&self, theme: &Theme) {
+        let x = self
+            .battery_x(theme)
+            .saturating_sub(theme.padding / 2 + ICON_WIDTH);
+        let y = self.icon_y(USB_PLUG.len() as u16);
+        let rect = Rect::new(x, y, ICON_WIDTH, USB_PLUG.len() as u16);
+        eadk::push_rect_uniform(rect, theme.accent);
+        if self.status.plugged {
+            draw_icon(&USB_PLUG, x, y, |_| theme.on_accent);
+        }
+    }
+
+    fn draw_clock(&self, theme: &Theme) {
+        let Some(minutes) = self.status.minutes else {
+            return;
+        };
+        let text = format!("{:02}:{:02}", minutes / 60, minutes % 60);
+        let rect = Rect::new(
+            self.rect.x + theme.padding,
+            self.rect.y,
+            ui::text_width(&text),
+            self.rect.height,
+        );
+        ui::draw_text(&text, rect, rect.x, theme.on_accent, theme.accent);
+    }
+}
+
+impl Widget for StatusBar {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, _focused: bool) {
+        let theme = theme::current();
+        eadk::push_rect_uniform(self.rect, theme.accent);
+        let x = self.rect.x + self.rect.width.saturating_sub(ui::text_width(&self.title)) / 2;
+        ui::draw_text(&self.title, self.rect, x, theme.on_accent, theme.accent);
+        self.draw_battery(&theme);
+        self.draw_usb(&theme);
+        self.draw_clock(&theme);
+    }
+
+    fn is_focusable(&self) -> bool {
+        false
+    }
+}
+
+/// Draws the set bits of an icon, each row being a byte, with the color given
+/// for the x of each pixel.
+fn draw_icon(rows: &[u8], x: u16, y: u16, color: impl Fn(u16) -> Color) {
+    for (row, &bits) in rows.iter().enumerate() {
+        for column in 0..ICON_WIDTH {
+            if bits & (0x80 >> column) != 0 {
+                let px = x + column;
+                eadk::push_rect_uniform(Rect::new_pixel(px, y + row as u16), color(px));
+            }
+        }
+    }
+}
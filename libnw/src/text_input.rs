@@ -0,0 +1,538 @@
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+
+use crate::display::{
+    self, CHAR_HEIGHT, CHAR_WIDTH, Color, LARGE_CHAR_HEIGHT, LARGE_CHAR_WIDTH, Rect,
+};
+use crate::keyboard::{self, Key};
+use crate::theme;
+
+/// The horizontal space between the border of the field and the text, in pixels.
+const PADDING: u16 = 2;
+/// The space kept on the right of the field for the label of the layer, with
+/// the small font: the longest label and some padding, in pixels.
+const LAYER_LABEL_WIDTH: u16 = 5 * CHAR_WIDTH + PADDING;
+
+/// The clipboard shared by all the text fields of the app.
+///
+/// It is not the system clipboard, which is not exposed by `eadk`.
+static mut CLIPBOARD: Vec<char> = Vec::new();
+
+fn with_clipboard<R>(f: impl FnOnce(&mut Vec<char>) -> R) -> R {
+    let clipboard = &raw mut CLIPBOARD;
+    // The calculator runs a single thread and `f` never calls back into this function.
+    unsafe { f(&mut *clipboard) }
+}
+
+/// Returns the content of the app clipboard.
+pub fn clipboard() -> String {
+    with_clipboard(|clipboard| clipboard.iter().collect())
+}
+
+/// Replaces the content of the app clipboard.
+pub fn set_clipboard(text: &str) {
+    with_clipboard(|clipboard| {
+        clipboard.clear();
+        clipboard.extend(text.chars());
+    });
+}
+
+/// The layer the next keypress will be read from.
+///
+/// The firmware already applies the layers to the events, this is only
+/// tracked so it can be shown to the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Default,
+    Shift,
+    /// Alpha for the next keypress only.
+    Alpha,
+    /// Alpha until it is unlocked.
+    AlphaLock,
+}
+
+impl Layer {
+    /// A short text describing the layer, empty for the default layer.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Default => "",
+            Self::Shift => "shift",
+            Self::Alpha => "alpha",
+            Self::AlphaLock => "ALPHA",
+        }
+    }
+}
+
+/// What happened after a key has been given to a [`TextInput`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The key does nothing in a text field (`Up`, `Down`, `Home`...).
+    Ignored,
+    /// The text, the cursor or the layer changed, the field should be redrawn.
+    Handled,
+    /// The user pressed `Ok` or `Exe`.
+    Submitted,
+    /// The user pressed `Back`.
+    Cancelled,
+}
+
+/// A single line text field.
+///
+/// Feed it keys with [`TextInput::handle_key`] and draw it with [`TextInput::draw`],
+/// or let [`TextInput::run`] do both until the user is done.
+pub struct TextInput {
+    text: Vec<char>,
+    /// The position of the cursor, in chars.
+    cursor: usize,
+    /// The other end of the selection, the cursor being the first.
+    anchor: Option<usize>,
+    /// The index of the first visible char.
+    scroll: usize,
+    layer: Layer,
+    rect: Rect,
+    large_font: bool,
+    max_len: usize,
+    filter: fn(char) -> bool,
+    pub text_color: Color,
+    pub background_color: Color,
+    pub selection_color: Color,
+}
+
+impl TextInput {
+    /// Creates an empty field drawn in the given rect, with the colors and the
+    /// font of the current theme.
+    pub fn new(rect: Rect) -> Self {
+        let theme = theme::current();
+        Self {
+            text: Vec::new(),
+            cursor: 0,
+            anchor: None,
+            scroll: 0,
+            layer: Layer::Default,
+            rect,
+            large_font: theme.large_font,
+            max_len: usize::MAX,
+            filter: |_| true,
+            text_color: theme.text,
+            background_color: theme.background,
+            selection_color: theme.selection,
+        }
+    }
+
+    /// Uses the large font instead of the small one.
+    pub fn with_large_font(mut self, large_font: bool) -> Self {
+        self.large_font = large_font;
+        self.scroll_to_cursor();
+        self
+    }
+
+    /// Limits the number of chars that can be typed.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self.text.truncate(max_len);
+        self.cursor = self.cursor.min(self.text.len());
+        self.anchor = None;
+        self.scroll_to_cursor();
+        self
+    }
+
+    /// Only accepts the chars for which `filter` returns true,
+    /// for example `|c| c.is_ascii_digit()` for a number.
+    pub fn with_filter(mut self, filter: fn(char) -> bool) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Replaces the text and puts the cursor at its end.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// The content of the field.
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    /// Replaces the text and puts the cursor at its end.
+    ///
+    /// Chars refused by the filter are dropped.
+    pub fn set_text(&mut self, text: &str) {
+        self.text.clear();
+        self.cursor = 0;
+        self.anchor = None;
+        self.insert(text.chars());
+    }
+
+    /// The position of the cursor, in chars.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The selected range of chars, if any.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        if anchor == self.cursor {
+            return None;
+        }
+        Some((self.cursor.min(anchor), self.cursor.max(anchor)))
+    }
+
+    /// The layer the next keypress will be read from.
+    pub fn layer(&self) -> Layer {
+        self.layer
+    }
+
+    /// The rect the field is drawn in.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Moves the field.
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.scroll_to_cursor();
+    }
+
+    /// Updates the field according to a keypress.
+    ///
+    /// `Copy` copies the selection, or the whole text without a selection.
+    /// `Cut` only removes the selection, and is ignored without one.
+    pub fn handle_key(&mut self, key: Key) -> Response {
+        if self.update_layer(key) {
+            return Response::Handled;
+        }
+        match key {
+            Key::Ok | Key::Exe => return Response::Submitted,
+            Key::Back => return Response::Cancelled,
+            Key::Left => {
+                self.cursor = match self.selection() {
+                    Some((start, _)) => start,
+                    None => self.cursor.saturating_sub(1),
+                };
+                self.anchor = None;
+            }
+            Key::Right => {
+                self.cursor = match self.selection() {
+                    Some((_, end)) => end,
+                    None => (self.cursor + 1).min(self.text.len()),
+                };
+                self.anchor = None;
+            }
+            Key::ShiftLeft => {
+                self.anchor.get_or_insert(self.cursor);
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            Key::ShiftRight => {
+                self.anchor.get_or_insert(self.cursor);
+                self.cursor = (self.cursor + 1).min(self.text.len());
+            }
+            Key::Backspace => {
+                if !self.delete_selection() && self.cursor > 0 {
+                    self.cursor -= 1;
+                    self.text.remove(self.cursor);
+                }
+            }
+            Key::Clear => {
+                self.text.clear();
+                self.cursor = 0;
+                self.anchor = None;
+            }
+            Key::Copy => set_clipboard(&self.selected_text()),
+            Key::Cut => {
+                if self.selection().is_none() {
+                    return Response::Ignored;
+                }
+                set_clipboard(&self.selected_text());
+                self.delete_selection();
+            }
+            Key::Paste => {
+                let pasted = with_clipboard(|clipboard| clipboard.clone());
+                self.delete_selection();
+                self.insert(pasted.into_iter());
+            }
+            _ => match key.to_char() {
+                Some(c) => {
+                    self.delete_selection();
+                    self.insert(core::iter::once(c));
+                }
+                None => return Response::Ignored,
+            },
+        }
+        self.scroll_to_cursor();
+        Response::Handled
+    }
+
+    /// Draws the field, with the cursor and the label of the layer on the right
+    /// if `focused` is true.
+    pub fn draw(&self, focused: bool) {
+        self.rect.fill(self.background_color);
+
+        let (char_width, char_height) = self.char_size();
+        let y = self.rect.y + self.rect.height.saturating_sub(char_height) / 2;
+        let x_of = |index: usize| self.rect.x + PADDING + (index - self.scroll) as u16 * char_width;
+        let visible_end = (self.scroll + self.visible_chars()).min(self.text.len());
+
+        let visible: String = self.text[self.scroll..visible_end].iter().collect();
+        display::draw_string(
+            &visible,
+            x_of(self.scroll),
+            y,
+            self.large_font,
+            self.text_color,
+            self.background_color,
+        );
+
+        if let Some((start, end)) = self.selection() {
+            let start = start.clamp(self.scroll, visible_end);
+            let end = end.clamp(self.scroll, visible_end);
+            if start < end {
+                let selected: String = self.text[start..end].iter().collect();
+                display::draw_string(
+                    &selected,
+                    x_of(start),
+                    y,
+                    self.large_font,
+                    self.text_color,
+                    self.selection_color,
+                );
+            }
+        }
+
+        if focused {
+            // The cursor is drawn on the left edge of the char it is before.
+            let x = x_of(self.cursor).saturating_sub(1);
+            Rect::new(x, y, 1, char_height).fill(self.text_color);
+
+            let label = self.layer.label();
+            let label_x = (self.rect.x + self.rect.width)
+                .saturating_sub(PADDING + label.len() as u16 * CHAR_WIDTH);
+            let label_y = self.rect.y + self.rect.height.saturating_sub(CHAR_HEIGHT) / 2;
+            display::draw_string(
+                label,
+                label_x,
+                label_y,
+                false,
+                self.text_color,
+                self.background_color,
+            );
+        }
+    }
+
+    /// Draws the field and handles keys until the user submits or cancels.
+    ///
+    /// Returns the text if submitted, `None` if cancelled.
+    pub fn run(&mut self) -> Option<String> {
+        self.draw(true);
+        loop {
+            match self.handle_key(keyboard::wait_for_key()) {
+                Response::Ignored => (),
+                Response::Handled => self.draw(true),
+                Response::Submitted => return Some(self.text()),
+                Response::Cancelled => return None,
+            }
+        }
+    }
+
+    /// Tracks the modifiers, returns true if the key was one.
+    fn update_layer(&mut self, key: Key) -> bool {
+        self.layer = match (key, self.layer) {
+            (Key::Shift, Layer::Shift) => Layer::Default,
+            (Key::Shift, _) => Layer::Shift,
+            (Key::Alpha, Layer::Alpha) => Layer::AlphaLock,
+            (Key::Alpha, Layer::AlphaLock) => Layer::Default,
+            (Key::Alpha, _) => Layer::Alpha,
+            (Key::AlphaLock, Layer::AlphaLock) => Layer::Default,
+            (Key::AlphaLock, _) => Layer::AlphaLock,
+            (_, Layer::AlphaLock) => return false,
+            _ => {
+                self.layer = Layer::Default;
+                return false;
+            }
+        };
+        true
+    }
+
+    fn selected_text(&self) -> String {
+        match self.selection() {
+            Some((start, end)) => self.text[start..end].iter().collect(),
+            None => self.text(),
+        }
+    }
+
+    /// Removes the selected chars, returns false if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.anchor = None;
+        match selection {
+            Some((start, end)) => {
+                self.text.drain(start..end);
+                self.cursor = start;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts chars at the cursor, skipping the ones refused by the filter.
+    fn insert(&mut self, chars: impl Iterator<Item = char>) {
+        for c in chars.filter(|c| (self.filter)(*c)) {
+            if self.text.len() >= self.max_len {
+                break;
+            }
+            self.text.insert(self.cursor, c);
+            self.cursor += 1;
+        }
+        self.scroll_to_cursor();
+    }
+
+    fn char_size(&self) -> (u16, u16) {
+        if self.large_font {
+            (LARGE_CHAR_WIDTH, LARGE_CHAR_HEIGHT)
+        } else {
+            (CHAR_WIDTH, CHAR_HEIGHT)
+        }
+    }
+
+    /// The number of chars that fit in the field, next to the label of the layer.
+    fn visible_chars(&self) -> usize {
+        let (char_width, _) = self.char_size();
+        let width = self.rect.width.saturating_sub(PADDING + LAYER_LABEL_WIDTH);
+        (width / char_width).max(1) as usize
+    }
+
+    /// Scrolls horizontally so the cursor is in the field.
+    fn scroll_to_cursor(&mut self) {
+        let visible = self.visible_chars();
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + visible {
+            // Keep room for the cursor after the last char.
+            self.scroll = self.cursor + 1 - visible;
+        }
+        // Don't leave blank space on the right when text was removed.
+        let max_scroll = (self.text.len() + 1).saturating_sub(visible);
+        self.scroll = self.scroll.min(max_scroll);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A field 10 small chars wide, next to the label of the layer.
+    fn field() -> TextInput {
+        TextInput::new(Rect::new(
+            0,
+            0,
+            PADDING + LAYER_LABEL_WIDTH + 10 * CHAR_WIDTH,
+            20,
+        ))
+    }
+
+    fn type_text(input: &mut TextInput, text: &str) {
+        for c in text.chars() {
+            input.handle_key(Key::from_char(c).unwrap());
+        }
+    }
+
+    #[test]
+    fn typing_and_moving() {
+        let mut input = field();
+        type_text(&mut input, "hllo");
+        assert_eq!(input.handle_key(Key::Left), Response::Handled);
+        for _ in 0..3 {
+            input.handle_key(Key::Left);
+        }
+        input.handle_key(Key::Right);
+        type_text(&mut input, "e");
+        assert_eq!(input.text(), "hello");
+        assert_eq!(input.cursor(), 2);
+        input.handle_key(Key::Backspace);
+        assert_eq!(input.text(), "hllo");
+        assert_eq!(input.handle_key(Key::Up), Response::Ignored);
+        assert_eq!(input.handle_key(Key::Exe), Response::Submitted);
+        assert_eq!(input.handle_key(Key::Back), Response::Cancelled);
+    }
+
+    #[test]
+    fn selection() {
+        let mut input = field().with_text("hello");
+        input.handle_key(Key::ShiftLeft);
+        input.handle_key(Key::ShiftLeft);
+        assert_eq!(input.selection(), Some((3, 5)));
+        input.handle_key(Key::Left);
+        assert_eq!(input.selection(), None);
+        assert_eq!(input.cursor(), 3);
+
+        input.handle_key(Key::ShiftRight);
+        type_text(&mut input, "p");
+        assert_eq!(input.text(), "helpo");
+    }
+
+    #[test]
+    fn cut_and_paste() {
+        let mut input = field().with_text("hello");
+        assert_eq!(input.handle_key(Key::Cut), Response::Ignored);
+        assert_eq!(input.text(), "hello");
+
+        input.handle_key(Key::ShiftLeft);
+        input.handle_key(Key::ShiftLeft);
+        input.handle_key(Key::Cut);
+        assert_eq!(input.text(), "hel");
+        input.handle_key(Key::Left);
+        input.handle_key(Key::Paste);
+        assert_eq!(input.text(), "helol");
+
+        input.handle_key(Key::Copy);
+        input.handle_key(Key::Clear);
+        input.handle_key(Key::Paste);
+        assert_eq!(input.text(), "helol");
+    }
+
+    #[test]
+    fn max_len_and_filter() {
+        let mut input = field().with_max_len(4).with_filter(|c| c.is_ascii_digit());
+        type_text(&mut input, "1a2b345");
+        assert_eq!(input.text(), "1234");
+
+        let input = field().with_text("123456").with_max_len(3);
+        assert_eq!(input.text(), "123");
+        assert_eq!(input.cursor(), 3);
+    }
+
+    #[test]
+    fn layers() {
+        let mut input = field();
+        assert_eq!(input.handle_key(Key::Shift), Response::Handled);
+        assert_eq!(input.layer(), Layer::Shift);
+        input.handle_key(Key::Shift);
+        assert_eq!(input.layer(), Layer::Default);
+
+        input.handle_key(Key::Alpha);
+        assert_eq!(input.layer(), Layer::Alpha);
+        type_text(&mut input, "a");
+        assert_eq!(input.layer(), Layer::Default);
+
+        input.handle_key(Key::Alpha);
+        input.handle_key(Key::Alpha);
+        assert_eq!(input.layer(), Layer::AlphaLock);
+        type_text(&mut input, "bc");
+        assert_eq!(input.layer(), Layer::AlphaLock);
+        input.handle_key(Key::Alpha);
+        assert_eq!(input.layer(), Layer::Default);
+    }
+
+    #[test]
+    fn scrolling() {
+        let mut input = field().with_text("0123456789abc");
+        assert_eq!(input.scroll, 4);
+        for _ in 0..13 {
+            input.handle_key(Key::Left);
+        }
+        assert_eq!(input.scroll, 0);
+        input.handle_key(Key::Clear);
+        assert_eq!(input.scroll, 0);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::display::{CHAR_HEIGHT, CHAR_WIDTH, Color, LARGE_CHAR_HEIGHT, LARGE_CHAR_WIDTH};
+
+/// The colors, font and spacing used to draw the widgets, dialogs, plots and charts.
+///
+/// The current theme is global, the widgets read it each time they are drawn:
+///
+/// ```ignore
+/// theme::set_current(Theme::DARK);
+/// form.draw();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub background: Color,
+    pub text: Color,
+    /// Hints, values next to labels and the labels of the axes.
+    pub secondary_text: Color,
+    /// The title bars and the active parts of the controls.
+    pub accent: Color,
+    /// The text drawn over the accent color.
+    pub on_accent: Color,
+    /// The background of the focused row.
+    pub selection: Color,
+    /// Borders, and the inactive parts of the controls.
+    pub border: Color,
+    /// The separators between rows, the unselected tabs and the grids.
+    pub separator: Color,
+    /// Errors and the panic screen.
+    pub error: Color,
+    pub success: Color,
+    /// Whether the widgets write with the large font. Plots and charts always
+    /// use the small one.
+    pub large_font: bool,
+    /// The space between the border of a row and its text, in pixels.
+    pub padding: u16,
+}
+
+impl Theme {
+    /// The black on white of the firmware apps.
+    pub const LIGHT: Self = Self {
+        background: Color::WHITE,
+        text: Color::BLACK,
+        secondary_text: Color::from_rgb(0x6E, 0x6E, 0x6E),
+        accent: Color::from_rgb(0xFF, 0xB7, 0x34),
+        on_accent: Color::WHITE,
+        selection: Color::from_rgb(0xD4, 0xD7, 0xE0),
+        border: Color::from_rgb(0xD9, 0xD9, 0xD9),
+        separator: Color::from_rgb(0xEE, 0xEE, 0xEE),
+        error: Color::RED,
+        success: Color::from_rgb(0x46, 0xBA, 0x3D),
+        large_font: false,
+        padding: 8,
+    };
+
+    /// Light text on a dark grey background, with the same accent.
+    pub const DARK: Self = Self {
+        background: Color::from_rgb(0x1E, 0x1F, 0x24),
+        text: Color::WHITE,
+        secondary_text: Color::from_rgb(0xA0, 0xA3, 0xAA),
+        accent: Color::from_rgb(0xFF, 0xB7, 0x34),
+        on_accent: Color::WHITE,
+        selection: Color::from_rgb(0x3A, 0x3F, 0x4B),
+        border: Color::from_rgb(0x50, 0x52, 0x58),
+        separator: Color::from_rgb(0x30, 0x32, 0x38),
+        error: Color::from_rgb(0xFF, 0x50, 0x50),
+        success: Color::from_rgb(0x5C, 0xD6, 0x5C),
+        large_font: false,
+        padding: 8,
+    };
+
+    pub fn with_large_font(mut self, large_font: bool) -> Self {
+        self.large_font = large_font;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// The size of a char of the font of the theme, as `(width, height)`.
+    pub fn char_size(&self) -> (u16, u16) {
+        if self.large_font {
+            (LARGE_CHAR_WIDTH, LARGE_CHAR_HEIGHT)
+        } else {
+            (CHAR_WIDTH, CHAR_HEIGHT)
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::LIGHT
+    }
+}
+
+static mut CURRENT: Theme = Theme::LIGHT;
+
+/// The theme used to draw, [`Theme::LIGHT`] until another one is set.
+pub fn current() -> Theme {
+    // The calculator runs a single thread.
+    unsafe { CURRENT }
+}
+
+/// Changes the theme used to draw. What is already on the screen has to be
+/// drawn again to follow it.
+pub fn set_current(theme: Theme) {
+    unsafe { CURRENT = theme };
+}
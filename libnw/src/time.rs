@@ -1,7 +1,146 @@
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+
+pub use core::time::Duration;
+
 pub use eadk::millis as monotonic;
 pub use eadk::msleep;
 pub use eadk::usleep;
 
+/// A point in time, as measured by [`monotonic`].
+///
+/// The resolution is one millisecond.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// The current instant.
+    pub fn now() -> Self {
+        Self(monotonic())
+    }
+
+    /// Creates an instant from a value returned by [`monotonic`].
+    pub const fn from_millis(ms: u64) -> Self {
+        Self(ms)
+    }
+
+    /// The number of milliseconds since the start, as returned by [`monotonic`].
+    pub const fn as_millis(&self) -> u64 {
+        self.0
+    }
+
+    /// The time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Self::now().saturating_duration_since(*self)
+    }
+
+    /// The time elapsed from `earlier` to this instant.
+    ///
+    /// # Panics
+    /// Panics if `earlier` is later than this instant.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier)
+            .expect("supplied instant is later than self")
+    }
+
+    /// The time elapsed from `earlier` to this instant,
+    /// or `None` if `earlier` is later than this instant.
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        self.0.checked_sub(earlier.0).map(Duration::from_millis)
+    }
+
+    /// The time elapsed from `earlier` to this instant,
+    /// or zero if `earlier` is later than this instant.
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier)
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// The instant `duration` after this one, or `None` on overflow.
+    ///
+    /// The duration is truncated to the millisecond.
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        let ms = u64::try_from(duration.as_millis()).ok()?;
+        self.0.checked_add(ms).map(Self)
+    }
+
+    /// The instant `duration` before this one, or `None` if it is before the start.
+    ///
+    /// The duration is truncated to the millisecond.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        let ms = u64::try_from(duration.as_millis()).ok()?;
+        self.0.checked_sub(ms).map(Self)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    /// # Panics
+    /// Panics on overflow, see [`Instant::checked_add`].
+    fn add(self, duration: Duration) -> Instant {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    /// # Panics
+    /// Panics if the result is before the start, see [`Instant::checked_sub`].
+    fn sub(self, duration: Duration) -> Instant {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    /// # Panics
+    /// Panics if `earlier` is later than this instant, see [`Instant::duration_since`].
+    fn sub(self, earlier: Instant) -> Duration {
+        self.duration_since(earlier)
+    }
+}
+
+/// Blocks the thread for the given duration.
+///
+/// Unlike [`msleep`] and [`usleep`], there is no limit to the duration.
+pub fn sleep(duration: Duration) {
+    let mut ms = duration.as_millis();
+    while ms > 0 {
+        let chunk = ms.min(u32::MAX as u128) as u32;
+        msleep(chunk);
+        ms -= chunk as u128;
+    }
+    let us = duration.subsec_micros() % 1000;
+    if us > 0 {
+        usleep(us);
+    }
+}
+
+/// Blocks the thread until the given instant.
+///
+/// Returns immediately if the instant is in the past.
+pub fn sleep_until(deadline: Instant) {
+    if let Some(duration) = deadline.checked_duration_since(Instant::now()) {
+        sleep(duration);
+    }
+}
+
 /// Interface with the raw `eadk` C api.
 ///
 /// If you don't know what you are doing, use the safe rust implementations.
@@ -0,0 +1,960 @@
+extern crate alloc;
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::backlight::{self, Brightness};
+use crate::display::{self, Color, Rect, eadk};
+use crate::keyboard::{self, Key};
+use crate::text_input::TextInput;
+use crate::theme;
+
+pub use crate::text_input::Response;
+
+/// The height of a [`TitleBar`], in pixels.
+pub const TITLE_BAR_HEIGHT: u16 = 18;
+/// The height of a row of a [`List`] and of most controls, in pixels.
+pub const ROW_HEIGHT: u16 = 24;
+
+/// A part of the interface the user can look at and, if focusable, interact with.
+///
+/// Widgets are drawn in their own rect. Keys are given to the focused widget,
+/// which returns [`Response::Ignored`] for the keys it doesn't use so they can
+/// move the focus instead.
+pub trait Widget {
+    fn rect(&self) -> Rect;
+
+    fn set_rect(&mut self, rect: Rect);
+
+    /// Draws the whole widget, highlighted if it has the focus.
+    fn draw(&self, focused: bool);
+
+    /// Reacts to a keypress while the widget has the focus.
+    fn handle_key(&mut self, key: Key) -> Response {
+        let _ = key;
+        Response::Ignored
+    }
+
+    /// Whether the widget can have the focus.
+    fn is_focusable(&self) -> bool {
+        true
+    }
+}
+
+impl Widget for TextInput {
+    fn rect(&self) -> Rect {
+        TextInput::rect(self)
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        TextInput::set_rect(self, rect);
+    }
+
+    fn draw(&self, focused: bool) {
+        TextInput::draw(self, focused);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        TextInput::handle_key(self, key)
+    }
+}
+
+/// Draws a text in the font of the theme, vertically centered in a rect, cut
+/// to fit its width.
+pub(crate) fn draw_text(text: &str, rect: Rect, x: u16, color: Color, background: Color) {
+    let theme = theme::current();
+    let (char_width, char_height) = theme.char_size();
+    let fitting = (rect.x + rect.width).saturating_sub(x) / char_width;
+    let text: String = text.chars().take(fitting as usize).collect();
+    let y = rect.y + rect.height.saturating_sub(char_height) / 2;
+    display::draw_string(&text, x, y, theme.large_font, color, background);
+}
+
+/// The width of a text in the font of the theme, in pixels.
+pub(crate) fn text_width(text: &str) -> u16 {
+    text.chars().count() as u16 * theme::current().char_size().0
+}
+
+/// The background of a row, depending on the focus.
+pub(crate) fn row_background(focused: bool) -> Color {
+    let theme = theme::current();
+    if focused {
+        theme.selection
+    } else {
+        theme.background
+    }
+}
+
+/// Draws a 1 pixel wide border inside a rect.
+pub(crate) fn draw_border(rect: Rect, color: Color) {
+    let Rect {
+        x,
+        y,
+        width,
+        height,
+    } = rect;
+    if width == 0 || height == 0 {
+        return;
+    }
+    eadk::push_rect_uniform(Rect::new(x, y, width, 1), color);
+    eadk::push_rect_uniform(Rect::new(x, y + height - 1, width, 1), color);
+    eadk::push_rect_uniform(Rect::new(x, y, 1, height), color);
+    eadk::push_rect_uniform(Rect::new(x + width - 1, y, 1, height), color);
+}
+
+/// The yellow bar at the top of the firmware apps, with a centered title.
+pub struct TitleBar {
+    rect: Rect,
+    title: String,
+}
+
+impl TitleBar {
+    /// Creates a title bar at the top of the screen.
+    pub fn new(title: &str) -> Self {
+        Self {
+            rect: Rect::new(0, 0, display::SCREEN_WIDTH, TITLE_BAR_HEIGHT),
+            title: String::from(title),
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = String::from(title);
+    }
+}
+
+impl Widget for TitleBar {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, _focused: bool) {
+        let theme = theme::current();
+        eadk::push_rect_uniform(self.rect, theme.accent);
+        let x = self.rect.x + self.rect.width.saturating_sub(text_width(&self.title)) / 2;
+        draw_text(&self.title, self.rect, x, theme.on_accent, theme.accent);
+    }
+
+    fn is_focusable(&self) -> bool {
+        false
+    }
+}
+
+/// A scrolling list of items, one of them being selected.
+///
+/// `Up` and `Down` move the selection, `Ok` or `Exe` submit it and `Back` cancels.
+pub struct List {
+    rect: Rect,
+    items: Vec<String>,
+    selected: usize,
+    /// The index of the first visible item.
+    scroll: usize,
+}
+
+impl List {
+    pub fn new(rect: Rect, items: &[&str]) -> Self {
+        Self {
+            rect,
+            items: items.iter().map(|&item| String::from(item)).collect(),
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Selects an item, clamped to the last one.
+    pub fn with_selected(mut self, selected: usize) -> Self {
+        self.set_selected(selected);
+        self
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn set_items(&mut self, items: &[&str]) {
+        self.items = items.iter().map(|&item| String::from(item)).collect();
+        self.set_selected(self.selected);
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The text of the selected item, `None` if the list is empty.
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected).map(String::as_str)
+    }
+
+    pub fn set_selected(&mut self, selected: usize) {
+        self.selected = selected.min(self.items.len().saturating_sub(1));
+        self.scroll_to_selection();
+    }
+
+    /// Shows the list until an item is submitted, returns its index,
+    /// or `None` if the user pressed `Back`.
+    pub fn run(&mut self) -> Option<usize> {
+        self.draw(true);
+        loop {
+            match self.handle_key(keyboard::wait_for_key()) {
+                Response::Ignored => (),
+                Response::Handled => self.draw(true),
+                Response::Submitted => return Some(self.selected),
+                Response::Cancelled => return None,
+            }
+        }
+    }
+
+    fn visible_rows(&self) -> usize {
+        (self.rect.height / ROW_HEIGHT).max(1) as usize
+    }
+
+    fn scroll_to_selection(&mut self) {
+        let rows = self.visible_rows();
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+    }
+}
+
+impl Widget for List {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.scroll_to_selection();
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let rows = self.visible_rows();
+        for row in 0..rows {
+            let rect = Rect::new(
+                self.rect.x,
+                self.rect.y + row as u16 * ROW_HEIGHT,
+                self.rect.width,
+                ROW_HEIGHT.min(self.rect.height),
+            );
+            let index = self.scroll + row;
+            let background = row_background(focused && index == self.selected);
+            eadk::push_rect_uniform(rect, background);
+            if let Some(item) = self.items.get(index) {
+                draw_text(item, rect, rect.x + theme.padding, theme.text, background);
+                let separator = Rect::new(rect.x, rect.y + rect.height - 1, rect.width, 1);
+                eadk::push_rect_uniform(separator, theme.separator);
+            }
+        }
+        // The pixels under the last full row.
+        let used = rows as u16 * ROW_HEIGHT;
+        if used < self.rect.height {
+            let rest = Rect::new(
+                self.rect.x,
+                self.rect.y + used,
+                self.rect.width,
+                self.rect.height - used,
+            );
+            eadk::push_rect_uniform(rest, theme.background);
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        match key {
+            Key::Up if self.selected > 0 => self.set_selected(self.selected - 1),
+            Key::Down if self.selected + 1 < self.items.len() => {
+                self.set_selected(self.selected + 1)
+            }
+            Key::Ok | Key::Exe if !self.items.is_empty() => return Response::Submitted,
+            Key::Back => return Response::Cancelled,
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+}
+
+/// An on/off switch with a label, flipped with `Ok` or `Exe`.
+pub struct Toggle {
+    rect: Rect,
+    label: String,
+    on: bool,
+}
+
+impl Toggle {
+    pub fn new(rect: Rect, label: &str, on: bool) -> Self {
+        Self {
+            rect,
+            label: String::from(label),
+            on,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    pub fn set_on(&mut self, on: bool) {
+        self.on = on;
+    }
+}
+
+impl Widget for Toggle {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let background = row_background(focused);
+        eadk::push_rect_uniform(self.rect, background);
+        draw_text(
+            &self.label,
+            self.rect,
+            self.rect.x + theme.padding,
+            theme.text,
+            background,
+        );
+
+        const TRACK_WIDTH: u16 = 28;
+        const TRACK_HEIGHT: u16 = 14;
+        let track = Rect::new(
+            (self.rect.x + self.rect.width).saturating_sub(TRACK_WIDTH + theme.padding),
+            self.rect.y + self.rect.height.saturating_sub(TRACK_HEIGHT) / 2,
+            TRACK_WIDTH,
+            TRACK_HEIGHT,
+        );
+        let track_color = if self.on { theme.accent } else { theme.border };
+        eadk::push_rect_uniform(track, track_color);
+        let knob_size = TRACK_HEIGHT - 4;
+        let knob_x = if self.on {
+            track.x + TRACK_WIDTH - knob_size - 2
+        } else {
+            track.x + 2
+        };
+        eadk::push_rect_uniform(
+            Rect::new_square(knob_x, track.y + 2, knob_size),
+            theme.on_accent,
+        );
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        match key {
+            Key::Ok | Key::Exe => {
+                self.on = !self.on;
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+}
+
+/// A value picked in a range by moving a knob with `Left` and `Right`.
+pub struct Slider {
+    rect: Rect,
+    label: String,
+    value: f32,
+    min: f32,
+    max: f32,
+    step: f32,
+}
+
+impl Slider {
+    /// Creates a slider from `min` to `max`, moving by tenths of the range.
+    ///
+    /// A NaN `min` counts as 0, and a `max` below `min` or NaN as `min`.
+    pub fn new(rect: Rect, label: &str, min: f32, max: f32, value: f32) -> Self {
+        let min = if min.is_nan() { 0.0 } else { min };
+        let max = max.max(min);
+        Self {
+            rect,
+            label: String::from(label),
+            value: value.clamp(min, max),
+            min,
+            max,
+            step: (max - min) / 10.0,
+        }
+    }
+
+    /// Changes how much a keypress moves the knob, at least the smallest
+    /// positive `f32`.
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step.max(f32::MIN_POSITIVE);
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+}
+
+impl Widget for Slider {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let background = row_background(focused);
+        eadk::push_rect_uniform(self.rect, background);
+        draw_text(
+            &self.label,
+            self.rect,
+            self.rect.x + theme.padding,
+            theme.text,
+            background,
+        );
+
+        // The track takes the right half of the row.
+        const KNOB_WIDTH: u16 = 6;
+        const KNOB_HEIGHT: u16 = 14;
+        let left = self.rect.x + self.rect.width / 2;
+        let width = (self.rect.width / 2).saturating_sub(theme.padding + KNOB_WIDTH);
+        let center_y = self.rect.y + self.rect.height / 2;
+        let span = self.max - self.min;
+        let t = if span > 0.0 {
+            (self.value - self.min) / span
+        } else {
+            0.0
+        };
+        let filled = libm::roundf(t * width as f32) as u16;
+        let track_x = left + KNOB_WIDTH / 2;
+        eadk::push_rect_uniform(Rect::new(track_x, center_y - 1, filled, 3), theme.accent);
+        eadk::push_rect_uniform(
+            Rect::new(track_x + filled, center_y - 1, width - filled, 3),
+            theme.border,
+        );
+        let knob = Rect::new(
+            left + filled,
+            center_y.saturating_sub(KNOB_HEIGHT / 2),
+            KNOB_WIDTH,
+            KNOB_HEIGHT,
+        );
+        eadk::push_rect_uniform(knob, theme.accent);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        let value = match key {
+            Key::Left | Key::Minus => self.value - self.step,
+            Key::Right | Key::Plus => self.value + self.step,
+            _ => return Response::Ignored,
+        };
+        let previous = self.value;
+        self.set_value(value);
+        if self.value == previous {
+            Response::Ignored
+        } else {
+            Response::Handled
+        }
+    }
+}
+
+/// A [`Slider`] over the 16 levels of [`Brightness`], changing the brightness
+/// of the screen as it moves.
+pub struct BrightnessSlider {
+    slider: Slider,
+}
+
+impl BrightnessSlider {
+    /// Creates a slider at the current brightness.
+    pub fn new(rect: Rect, label: &str) -> Self {
+        let max = Brightness::MAX.level() as f32;
+        let level = backlight::get_level().level() as f32;
+        Self {
+            slider: Slider::new(rect, label, 0.0, max, level).with_step(1.0),
+        }
+    }
+
+    /// The brightness under the knob.
+    pub fn brightness(&self) -> Brightness {
+        Brightness::saturating(self.slider.value() as u8)
+    }
+
+    /// Moves the knob and changes the brightness of the screen.
+    pub fn set_brightness(&mut self, brightness: Brightness) {
+        self.slider.set_value(brightness.level() as f32);
+        backlight::set_level(brightness);
+    }
+}
+
+impl Widget for BrightnessSlider {
+    fn rect(&self) -> Rect {
+        self.slider.rect()
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.slider.set_rect(rect);
+    }
+
+    fn draw(&self, focused: bool) {
+        self.slider.draw(focused);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        let response = self.slider.handle_key(key);
+        if response == Response::Handled {
+            backlight::set_level(self.brightness());
+        }
+        response
+    }
+}
+
+/// A whole number picked with `Left` and `Right` (or `-` and `+`), shown as `< 12 >`.
+pub struct Spinner {
+    rect: Rect,
+    label: String,
+    value: i32,
+    min: i32,
+    max: i32,
+    step: i32,
+}
+
+impl Spinner {
+    pub fn new(rect: Rect, label: &str, min: i32, max: i32, value: i32) -> Self {
+        Self {
+            rect,
+            label: String::from(label),
+            value: value.clamp(min, max.max(min)),
+            min,
+            max: max.max(min),
+            step: 1,
+        }
+    }
+
+    pub fn with_step(mut self, step: i32) -> Self {
+        self.step = step.max(1);
+        self
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+}
+
+impl Widget for Spinner {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let background = row_background(focused);
+        eadk::push_rect_uniform(self.rect, background);
+        draw_text(
+            &self.label,
+            self.rect,
+            self.rect.x + theme.padding,
+            theme.text,
+            background,
+        );
+        let less = if self.value > self.min { '<' } else { ' ' };
+        let more = if self.value < self.max { '>' } else { ' ' };
+        let text = format!("{} {} {}", less, self.value, more);
+        let x = (self.rect.x + self.rect.width).saturating_sub(text_width(&text) + theme.padding);
+        draw_text(&text, self.rect, x, theme.secondary_text, background);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        let value = match key {
+            Key::Left | Key::Minus => self.value.saturating_sub(self.step),
+            Key::Right | Key::Plus => self.value.saturating_add(self.step),
+            _ => return Response::Ignored,
+        };
+        let previous = self.value;
+        self.set_value(value);
+        if self.value == previous {
+            Response::Ignored
+        } else {
+            Response::Handled
+        }
+    }
+}
+
+/// A button, submitted with `Ok` or `Exe`.
+pub struct Button {
+    rect: Rect,
+    label: String,
+}
+
+impl Button {
+    pub fn new(rect: Rect, label: &str) -> Self {
+        Self {
+            rect,
+            label: String::from(label),
+        }
+    }
+}
+
+impl Widget for Button {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let background = row_background(focused);
+        eadk::push_rect_uniform(self.rect, background);
+        draw_border(self.rect, theme.border);
+        let x = self.rect.x + self.rect.width.saturating_sub(text_width(&self.label)) / 2;
+        draw_text(&self.label, self.rect, x, theme.text, background);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        match key {
+            Key::Ok | Key::Exe => Response::Submitted,
+            _ => Response::Ignored,
+        }
+    }
+}
+
+/// A box with a label, checked and unchecked with `Ok` or `Exe`.
+pub struct Checkbox {
+    rect: Rect,
+    label: String,
+    checked: bool,
+}
+
+impl Checkbox {
+    pub fn new(rect: Rect, label: &str, checked: bool) -> Self {
+        Self {
+            rect,
+            label: String::from(label),
+            checked,
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+}
+
+impl Widget for Checkbox {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let background = row_background(focused);
+        eadk::push_rect_uniform(self.rect, background);
+
+        const BOX_SIZE: u16 = 12;
+        let square = Rect::new_square(
+            self.rect.x + theme.padding,
+            self.rect.y + self.rect.height.saturating_sub(BOX_SIZE) / 2,
+            BOX_SIZE,
+        );
+        eadk::push_rect_uniform(square, theme.background);
+        draw_border(square, theme.secondary_text);
+        if self.checked {
+            let inside = Rect::new_square(square.x + 3, square.y + 3, BOX_SIZE - 6);
+            eadk::push_rect_uniform(inside, theme.accent);
+        }
+        let x = square.x + BOX_SIZE + theme.padding;
+        draw_text(&self.label, self.rect, x, theme.text, background);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        match key {
+            Key::Ok | Key::Exe => {
+                self.checked = !self.checked;
+                Response::Handled
+            }
+            _ => Response::Ignored,
+        }
+    }
+}
+
+/// A bar filling up from left to right, from 0 to 1.
+pub struct ProgressBar {
+    rect: Rect,
+    progress: f32,
+}
+
+impl ProgressBar {
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            rect,
+            progress: 0.0,
+        }
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Changes the progress, clamped between 0 and 1.
+    pub fn set_progress(&mut self, progress: f32) {
+        self.progress = progress.clamp(0.0, 1.0);
+    }
+}
+
+impl Widget for ProgressBar {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, _focused: bool) {
+        let theme = theme::current();
+        let filled = libm::roundf(self.progress * self.rect.width as f32) as u16;
+        let done = Rect::new(self.rect.x, self.rect.y, filled, self.rect.height);
+        let rest = Rect::new(
+            self.rect.x + filled,
+            self.rect.y,
+            self.rect.width - filled,
+            self.rect.height,
+        );
+        eadk::push_rect_uniform(done, theme.accent);
+        eadk::push_rect_uniform(rest, theme.separator);
+    }
+
+    fn is_focusable(&self) -> bool {
+        false
+    }
+}
+
+/// A row of tabs of the same width, switched with `Left` and `Right`.
+pub struct TabBar {
+    rect: Rect,
+    tabs: Vec<String>,
+    selected: usize,
+}
+
+impl TabBar {
+    pub fn new(rect: Rect, tabs: &[&str]) -> Self {
+        Self {
+            rect,
+            tabs: tabs.iter().map(|&tab| String::from(tab)).collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, selected: usize) {
+        self.selected = selected.min(self.tabs.len().saturating_sub(1));
+    }
+}
+
+impl Widget for TabBar {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let count = self.tabs.len().max(1) as u16;
+        let width = self.rect.width / count;
+        for (index, tab) in self.tabs.iter().enumerate() {
+            // The last tab takes the pixels left by the division.
+            let x = self.rect.x + index as u16 * width;
+            let tab_width = if index + 1 == self.tabs.len() {
+                self.rect.x + self.rect.width - x
+            } else {
+                width
+            };
+            let rect = Rect::new(x, self.rect.y, tab_width, self.rect.height);
+            let selected = index == self.selected;
+            let background = if selected {
+                theme.background
+            } else {
+                theme.separator
+            };
+            let color = if selected {
+                theme.text
+            } else {
+                theme.secondary_text
+            };
+            eadk::push_rect_uniform(rect, background);
+            let text_x = x + tab_width.saturating_sub(text_width(tab)) / 2;
+            draw_text(tab, rect, text_x, color, background);
+            if selected && focused {
+                let underline = Rect::new(x, rect.y + rect.height.saturating_sub(2), tab_width, 2);
+                eadk::push_rect_uniform(underline, theme.accent);
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        match key {
+            Key::Left if self.selected > 0 => self.selected -= 1,
+            Key::Right if self.selected + 1 < self.tabs.len() => self.selected += 1,
+            _ => return Response::Ignored,
+        }
+        Response::Handled
+    }
+}
+
+/// A group of widgets, `Up` and `Down` moving the focus between the focusable ones.
+///
+/// The form borrows the widgets, read their values once it is done.
+///
+/// ```ignore
+/// let mut sound = Toggle::new(Rect::new(0, 18, 320, ROW_HEIGHT), "Sound", true);
+/// let mut level = Spinner::new(Rect::new(0, 42, 320, ROW_HEIGHT), "Level", 1, 10, 1);
+/// let mut start = Button::new(Rect::new(110, 80, 100, ROW_HEIGHT), "Start");
+/// let submitted = Form::new()
+///     .with(&mut TitleBar::new("Settings"))
+///     .with(&mut sound)
+///     .with(&mut level)
+///     .with(&mut start)
+///     .run();
+/// ```
+pub struct Form<'a> {
+    widgets: Vec<&'a mut dyn Widget>,
+    focus: Option<usize>,
+}
+
+impl<'a> Form<'a> {
+    pub fn new() -> Self {
+        Self {
+            widgets: Vec::new(),
+            focus: None,
+        }
+    }
+
+    /// Adds a widget below the others in the focus order.
+    /// The first focusable widget gets the focus.
+    pub fn with(mut self, widget: &'a mut dyn Widget) -> Self {
+        if self.focus.is_none() && widget.is_focusable() {
+            self.focus = Some(self.widgets.len());
+        }
+        self.widgets.push(widget);
+        self
+    }
+
+    /// The index of the focused widget.
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    /// Gives the focus to a widget, does nothing if it is not focusable.
+    pub fn set_focus(&mut self, index: usize) {
+        if self
+            .widgets
+            .get(index)
+            .is_some_and(|widget| widget.is_focusable())
+        {
+            self.focus = Some(index);
+        }
+    }
+
+    pub fn draw(&self) {
+        for (index, widget) in self.widgets.iter().enumerate() {
+            widget.draw(self.focus == Some(index));
+        }
+    }
+
+    /// Gives the key to the focused widget, or moves the focus if it ignores it.
+    pub fn handle_key(&mut self, key: Key) -> Response {
+        if let Some(focus) = self.focus {
+            let response = self.widgets[focus].handle_key(key);
+            if response != Response::Ignored {
+                return response;
+            }
+        }
+        match key {
+            Key::Up => self.move_focus(false),
+            Key::Down => self.move_focus(true),
+            Key::Back => Response::Cancelled,
+            _ => Response::Ignored,
+        }
+    }
+
+    /// Shows the form until a widget is submitted, returns its index,
+    /// or `None` if the user pressed `Back`.
+    pub fn run(&mut self) -> Option<usize> {
+        self.draw();
+        loop {
+            let focus = self.focus;
+            match self.handle_key(keyboard::wait_for_key()) {
+                Response::Ignored => (),
+                Response::Handled => {
+                    // Only redraw what changed: the widget, or the two ends of a focus move.
+                    let changed = if focus == self.focus {
+                        [focus, None]
+                    } else {
+                        [focus, self.focus]
+                    };
+                    for index in changed.into_iter().flatten() {
+                        self.widgets[index].draw(self.focus == Some(index));
+                    }
+                }
+                Response::Submitted => return self.focus,
+                Response::Cancelled => return None,
+            }
+        }
+    }
+
+    fn move_focus(&mut self, forward: bool) -> Response {
+        let Some(focus) = self.focus else {
+            return Response::Ignored;
+        };
+        let next = if forward {
+            (focus + 1..self.widgets.len()).find(|&i| self.widgets[i].is_focusable())
+        } else {
+            (0..focus).rev().find(|&i| self.widgets[i].is_focusable())
+        };
+        match next {
+            Some(next) => {
+                self.focus = Some(next);
+                Response::Handled
+            }
+            None => Response::Ignored,
+        }
+    }
+}
+
+impl Default for Form<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
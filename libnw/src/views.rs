@@ -0,0 +1,516 @@
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+
+use crate::display::{Color, Rect, eadk};
+use crate::keyboard::{self, Key};
+use crate::theme;
+use crate::ui::{self, ROW_HEIGHT, Response, Widget};
+
+/// The width of the scrollbar, in pixels.
+const SCROLLBAR_WIDTH: u16 = 3;
+/// The smallest height of the scrollbar thumb, so it stays visible in long lists.
+const MIN_THUMB_HEIGHT: u16 = 8;
+
+/// Gives the items of a [`ListView`] one at a time, so they don't all have to be in memory.
+pub trait ListProvider {
+    /// The number of items.
+    fn len(&self) -> usize;
+
+    /// The text of an item, only called for the visible ones.
+    fn item(&self, index: usize) -> String;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: ListProvider + ?Sized> ListProvider for &T {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn item(&self, index: usize) -> String {
+        (**self).item(index)
+    }
+}
+
+impl<S: AsRef<str>> ListProvider for [S] {
+    fn len(&self) -> usize {
+        <[S]>::len(self)
+    }
+
+    fn item(&self, index: usize) -> String {
+        String::from(self[index].as_ref())
+    }
+}
+
+impl<S: AsRef<str>> ListProvider for Vec<S> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn item(&self, index: usize) -> String {
+        String::from(self[index].as_ref())
+    }
+}
+
+/// A provider computing each item with a function.
+pub struct FnProvider<F> {
+    len: usize,
+    item: F,
+}
+
+/// Creates a provider of `len` items, computed by `item` when they are shown.
+///
+/// ```ignore
+/// let squares = from_fn(10_000, |i| format!("{}² = {}", i, i * i));
+/// ```
+pub fn from_fn<F: Fn(usize) -> String>(len: usize, item: F) -> FnProvider<F> {
+    FnProvider { len, item }
+}
+
+impl<F: Fn(usize) -> String> ListProvider for FnProvider<F> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn item(&self, index: usize) -> String {
+        (self.item)(index)
+    }
+}
+
+/// Gives the cells of a [`TableView`] one at a time.
+pub trait TableProvider {
+    fn rows(&self) -> usize;
+
+    fn columns(&self) -> usize;
+
+    /// The text of a cell, only called for the visible ones.
+    fn cell(&self, row: usize, column: usize) -> String;
+
+    /// The title of a column, shown in the header row.
+    fn header(&self, column: usize) -> String {
+        let _ = column;
+        String::new()
+    }
+}
+
+/// Which rows or columns are visible, and which one is selected.
+#[derive(Clone, Copy, Debug, Default)]
+struct Scroll {
+    selected: usize,
+    first: usize,
+}
+
+impl Scroll {
+    /// Moves the selection by `delta`, clamped in [0, len[, and scrolls to keep it
+    /// among the `visible` ones. Returns false if the selection didn't move.
+    fn move_by(&mut self, delta: isize, len: usize, visible: usize) -> bool {
+        let previous = self.selected;
+        self.select(self.selected.saturating_add_signed(delta), len, visible);
+        self.selected != previous
+    }
+
+    fn select(&mut self, index: usize, len: usize, visible: usize) {
+        self.selected = index.min(len.saturating_sub(1));
+        let visible = visible.max(1);
+        if self.selected < self.first {
+            self.first = self.selected;
+        } else if self.selected >= self.first + visible {
+            self.first = self.selected + 1 - visible;
+        }
+        // Don't leave empty rows at the end when the list shrinks.
+        self.first = self.first.min(len.saturating_sub(visible));
+    }
+}
+
+/// Draws a vertical scrollbar, showing which part of `total` rows is visible.
+fn draw_scrollbar(rect: Rect, first: usize, visible: usize, total: usize) {
+    let theme = theme::current();
+    eadk::push_rect_uniform(rect, theme.separator);
+    if total == 0 {
+        return;
+    }
+    let height = rect.height as usize;
+    let thumb_height = (height * visible.min(total) / total).max(MIN_THUMB_HEIGHT as usize);
+    let thumb_height = thumb_height.min(height);
+    let scrollable = total.saturating_sub(visible);
+    let top = ((height - thumb_height) * first.min(scrollable))
+        .checked_div(scrollable)
+        .unwrap_or(0);
+    let thumb = Rect::new(rect.x, rect.y + top as u16, rect.width, thumb_height as u16);
+    eadk::push_rect_uniform(thumb, theme.secondary_text);
+}
+
+/// A scrolling list drawing only its visible rows.
+///
+/// `Up` and `Down` move the selection, `Shift` + `Up` and `Shift` + `Down` move
+/// it by a page, `Ok` or `Exe` submit it and `Back` cancels.
+///
+/// ```ignore
+/// let mut view = ListView::new(Rect::SCREEN, from_fn(10_000, |i| format!("Item {}", i)));
+/// if let Some(index) = view.run() {
+///     // ...
+/// }
+/// ```
+pub struct ListView<P> {
+    rect: Rect,
+    provider: P,
+    scroll: Scroll,
+}
+
+impl<P: ListProvider> ListView<P> {
+    pub fn new(rect: Rect, provider: P) -> Self {
+        Self {
+            rect,
+            provider,
+            scroll: Scroll::default(),
+        }
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Gives access to the provider, to change the items.
+    ///
+    /// If items are removed, the selection is moved back to the last item
+    /// the next time the view is drawn or handles a key.
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.provider
+    }
+
+    pub fn selected(&self) -> usize {
+        self.scroll.selected
+    }
+
+    pub fn set_selected(&mut self, index: usize) {
+        let (len, rows) = (self.provider.len(), self.visible_rows());
+        self.scroll.select(index, len, rows);
+    }
+
+    /// Shows the list until an item is submitted, returns its index,
+    /// or `None` if the user pressed `Back`.
+    pub fn run(&mut self) -> Option<usize> {
+        self.draw(true);
+        loop {
+            match self.handle_key(keyboard::wait_for_key()) {
+                Response::Ignored => (),
+                Response::Handled => self.draw(true),
+                Response::Submitted => return Some(self.scroll.selected),
+                Response::Cancelled => return None,
+            }
+        }
+    }
+
+    fn visible_rows(&self) -> usize {
+        (self.rect.height / ROW_HEIGHT).max(1) as usize
+    }
+}
+
+impl<P: ListProvider> Widget for ListView<P> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        self.set_selected(self.scroll.selected);
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let len = self.provider.len();
+        let rows = self.visible_rows();
+        let mut scroll = self.scroll;
+        scroll.select(scroll.selected, len, rows);
+
+        let width = self.rect.width.saturating_sub(SCROLLBAR_WIDTH);
+        for row in 0..rows {
+            let y = self.rect.y + row as u16 * ROW_HEIGHT;
+            let rect = Rect::new(self.rect.x, y, width, ROW_HEIGHT.min(self.rect.height));
+            let index = scroll.first + row;
+            if index >= len {
+                eadk::push_rect_uniform(rect, theme.background);
+                continue;
+            }
+            let background = ui::row_background(focused && index == scroll.selected);
+            eadk::push_rect_uniform(rect, background);
+            let text = self.provider.item(index);
+            ui::draw_text(&text, rect, rect.x + theme.padding, theme.text, background);
+            let separator = Rect::new(rect.x, rect.y + rect.height - 1, rect.width, 1);
+            eadk::push_rect_uniform(separator, theme.separator);
+        }
+        let used = rows as u16 * ROW_HEIGHT;
+        if used < self.rect.height {
+            let rest = Rect::new(
+                self.rect.x,
+                self.rect.y + used,
+                width,
+                self.rect.height - used,
+            );
+            eadk::push_rect_uniform(rest, theme.background);
+        }
+
+        let bar = Rect::new(
+            self.rect.x + width,
+            self.rect.y,
+            SCROLLBAR_WIDTH,
+            self.rect.height,
+        );
+        draw_scrollbar(bar, scroll.first, rows, len);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        let (len, rows) = (self.provider.len(), self.visible_rows());
+        let page = rows as isize;
+        let delta = match key {
+            Key::Up => -1,
+            Key::Down => 1,
+            Key::ShiftUp => -page,
+            Key::ShiftDown => page,
+            Key::Ok | Key::Exe if len > 0 => return Response::Submitted,
+            Key::Back => return Response::Cancelled,
+            _ => return Response::Ignored,
+        };
+        if self.scroll.move_by(delta, len, rows) {
+            Response::Handled
+        } else {
+            Response::Ignored
+        }
+    }
+}
+
+/// A scrolling table drawing only its visible cells, with a header row.
+///
+/// The arrows move the selected cell, `Shift` + `Up` and `Shift` + `Down` move
+/// it by a page, `Ok` or `Exe` submit it and `Back` cancels.
+pub struct TableView<P> {
+    rect: Rect,
+    provider: P,
+    /// The width of each column, the last one being used for the columns after it.
+    column_widths: Vec<u16>,
+    rows: Scroll,
+    columns: Scroll,
+}
+
+impl<P: TableProvider> TableView<P> {
+    /// Creates a table with columns of 80 pixels.
+    pub fn new(rect: Rect, provider: P) -> Self {
+        Self {
+            rect,
+            provider,
+            column_widths: Vec::from([80]),
+            rows: Scroll::default(),
+            columns: Scroll::default(),
+        }
+    }
+
+    /// Sets the width of each column in pixels, the last one being used for the
+    /// columns after it.
+    pub fn with_column_widths(mut self, widths: &[u16]) -> Self {
+        if !widths.is_empty() {
+            self.column_widths = widths.iter().map(|&width| width.max(1)).collect();
+        }
+        self
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.provider
+    }
+
+    /// The selected cell, as (row, column).
+    pub fn selected(&self) -> (usize, usize) {
+        (self.rows.selected, self.columns.selected)
+    }
+
+    pub fn set_selected(&mut self, row: usize, column: usize) {
+        let visible_rows = self.visible_rows();
+        self.rows.select(row, self.provider.rows(), visible_rows);
+        self.columns.selected = column.min(self.provider.columns().saturating_sub(1));
+        self.scroll_to_column();
+    }
+
+    /// Shows the table until a cell is submitted, returns it as (row, column),
+    /// or `None` if the user pressed `Back`.
+    pub fn run(&mut self) -> Option<(usize, usize)> {
+        self.draw(true);
+        loop {
+            match self.handle_key(keyboard::wait_for_key()) {
+                Response::Ignored => (),
+                Response::Handled => self.draw(true),
+                Response::Submitted => return Some(self.selected()),
+                Response::Cancelled => return None,
+            }
+        }
+    }
+
+    fn column_width(&self, column: usize) -> u16 {
+        let last = self.column_widths.len() - 1;
+        self.column_widths[column.min(last)]
+    }
+
+    /// The rows under the header.
+    fn visible_rows(&self) -> usize {
+        (self.rect.height.saturating_sub(ROW_HEIGHT) / ROW_HEIGHT).max(1) as usize
+    }
+
+    fn content_width(&self) -> u16 {
+        self.rect.width.saturating_sub(SCROLLBAR_WIDTH)
+    }
+
+    /// Scrolls horizontally until the selected column is entirely visible.
+    fn scroll_to_column(&mut self) {
+        let selected = self.columns.selected;
+        if selected < self.columns.first {
+            self.columns.first = selected;
+        }
+        let width = self.content_width();
+        loop {
+            let used: u16 = (self.columns.first..=selected)
+                .map(|column| self.column_width(column))
+                .fold(0, u16::saturating_add);
+            if used <= width || self.columns.first == selected {
+                break;
+            }
+            self.columns.first += 1;
+        }
+    }
+
+    /// The visible columns and their position from the left of the table.
+    fn visible_columns(&self) -> Vec<(usize, u16, u16)> {
+        let width = self.content_width();
+        let mut x = 0;
+        let mut columns = Vec::new();
+        for column in self.columns.first..self.provider.columns() {
+            if x >= width {
+                break;
+            }
+            let column_width = self.column_width(column).min(width - x);
+            columns.push((column, x, column_width));
+            x += column_width;
+        }
+        columns
+    }
+
+    fn draw_cell(&self, text: &str, rect: Rect, color: Color, background: Color) {
+        let theme = theme::current();
+        eadk::push_rect_uniform(rect, background);
+        ui::draw_text(text, rect, rect.x + theme.padding / 2, color, background);
+        // The right and bottom borders.
+        let right = Rect::new(rect.x + rect.width - 1, rect.y, 1, rect.height);
+        let bottom = Rect::new(rect.x, rect.y + rect.height - 1, rect.width, 1);
+        eadk::push_rect_uniform(right, theme.border);
+        eadk::push_rect_uniform(bottom, theme.border);
+    }
+}
+
+impl<P: TableProvider> Widget for TableView<P> {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        let (row, column) = self.selected();
+        self.set_selected(row, column);
+    }
+
+    fn draw(&self, focused: bool) {
+        let theme = theme::current();
+        let rows = self.visible_rows();
+        let row_count = self.provider.rows();
+        let width = self.content_width();
+        let columns = self.visible_columns();
+        let used: u16 = columns.iter().map(|&(_, _, width)| width).sum();
+
+        // Header
+        for &(column, x, column_width) in &columns {
+            let rect = Rect::new(self.rect.x + x, self.rect.y, column_width, ROW_HEIGHT);
+            let header = self.provider.header(column);
+            self.draw_cell(&header, rect, theme.secondary_text, theme.separator);
+        }
+        if used < width {
+            let rest = Rect::new(self.rect.x + used, self.rect.y, width - used, ROW_HEIGHT);
+            eadk::push_rect_uniform(rest, theme.separator);
+        }
+
+        for row in 0..rows {
+            let y = self.rect.y + (row as u16 + 1) * ROW_HEIGHT;
+            let index = self.rows.first + row;
+            if index >= row_count {
+                let rect = Rect::new(self.rect.x, y, width, ROW_HEIGHT);
+                eadk::push_rect_uniform(rect, theme.background);
+                continue;
+            }
+            for &(column, x, column_width) in &columns {
+                let rect = Rect::new(self.rect.x + x, y, column_width, ROW_HEIGHT);
+                let selected =
+                    focused && index == self.rows.selected && column == self.columns.selected;
+                let background = if selected {
+                    theme.selection
+                } else {
+                    theme.background
+                };
+                let text = self.provider.cell(index, column);
+                self.draw_cell(&text, rect, theme.text, background);
+            }
+            if used < width {
+                let rest = Rect::new(self.rect.x + used, y, width - used, ROW_HEIGHT);
+                eadk::push_rect_uniform(rest, theme.background);
+            }
+        }
+        let bottom = (rows as u16 + 1) * ROW_HEIGHT;
+        if bottom < self.rect.height {
+            let rest = Rect::new(
+                self.rect.x,
+                self.rect.y + bottom,
+                width,
+                self.rect.height - bottom,
+            );
+            eadk::push_rect_uniform(rest, theme.background);
+        }
+
+        let bar = Rect::new(
+            self.rect.x + width,
+            self.rect.y,
+            SCROLLBAR_WIDTH,
+            self.rect.height,
+        );
+        draw_scrollbar(bar, self.rows.first, rows, row_count);
+    }
+
+    fn handle_key(&mut self, key: Key) -> Response {
+        let (row_count, rows) = (self.provider.rows(), self.visible_rows());
+        let page = rows as isize;
+        let moved = match key {
+            Key::Up => self.rows.move_by(-1, row_count, rows),
+            Key::Down => self.rows.move_by(1, row_count, rows),
+            Key::ShiftUp => self.rows.move_by(-page, row_count, rows),
+            Key::ShiftDown => self.rows.move_by(page, row_count, rows),
+            Key::Left | Key::Right => {
+                let previous = self.columns.selected;
+                let last = self.provider.columns().saturating_sub(1);
+                self.columns.selected = match key {
+                    Key::Left => previous.saturating_sub(1),
+                    _ => (previous + 1).min(last),
+                };
+                self.scroll_to_column();
+                self.columns.selected != previous
+            }
+            Key::Ok | Key::Exe if row_count > 0 => return Response::Submitted,
+            Key::Back => return Response::Cancelled,
+            _ => return Response::Ignored,
+        };
+        if moved {
+            Response::Handled
+        } else {
+            Response::Ignored
+        }
+    }
+}
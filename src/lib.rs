@@ -1,25 +1,37 @@
 #![no_std]
 
-use libnw::display::{self, CHAR_HEIGHT, Color, LARGE_CHAR_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH};
+use libnw::display::{self, CHAR_HEIGHT, LARGE_CHAR_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH};
+use libnw::scene::{Scene, SceneStack};
+use libnw::theme;
 
-/// How to handle the end of the main app.
-pub enum ExitBehaviour {
-    Exit,
-    Hang,
-    Restart,
-}
+pub use libnw::scene::ExitBehaviour;
 
 /// The core of the application logic
 pub fn main() -> ExitBehaviour {
-    const MESSAGE: &str = "Hey !!";
-    display::clear(Color::GREEN);
-    display::draw_string(
-        MESSAGE,
-        (SCREEN_WIDTH - (MESSAGE.len() as u16 * LARGE_CHAR_WIDTH)) / 2,
-        (SCREEN_HEIGHT - CHAR_HEIGHT) / 2,
-        true,
-        Color::BLACK,
-        Color::WHITE,
-    );
-    ExitBehaviour::Hang
+    SceneStack::new(Hello { drawn: false }).run()
+}
+
+/// The first and only scene of the app.
+struct Hello {
+    drawn: bool,
+}
+
+impl Scene for Hello {
+    fn draw(&mut self) {
+        if self.drawn {
+            return;
+        }
+        const MESSAGE: &str = "Hey !!";
+        let theme = theme::current();
+        display::clear(theme.success);
+        display::draw_string(
+            MESSAGE,
+            (SCREEN_WIDTH - (MESSAGE.len() as u16 * LARGE_CHAR_WIDTH)) / 2,
+            (SCREEN_HEIGHT - CHAR_HEIGHT) / 2,
+            true,
+            theme.text,
+            theme.background,
+        );
+        self.drawn = true;
+    }
 }
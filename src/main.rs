@@ -42,12 +42,13 @@ mod no_std {
     extern crate alloc;
 
     use alloc::format;
-    use libnw::display::{self, CHAR_HEIGHT, CHAR_WIDTH, Color, SCREEN_HEIGHT, SCREEN_WIDTH};
+    use libnw::display::{self, CHAR_HEIGHT, CHAR_WIDTH, SCREEN_HEIGHT, SCREEN_WIDTH};
+    use libnw::theme;
 
     /// This function is called when the application panics.
     #[panic_handler]
     fn panic(panic: &core::panic::PanicInfo<'_>) -> ! {
-        display::clear(Color::RED);
+        display::clear(theme::current().error);
         let error_msg = panic.message().as_str().unwrap_or("No panic message");
         render_error(error_msg);
         loop {}
@@ -56,7 +57,7 @@ mod no_std {
     /// This function is called when an allocation error occur.
     #[alloc_error_handler]
     fn alloc_error_handler(layout: core::alloc::Layout) -> ! {
-        display::clear(Color::RED);
+        display::clear(theme::current().error);
         let size_needed = layout.size();
         let error_msg = format!("Allocation error, {size_needed} bytes needed");
         render_error(&error_msg);
@@ -64,13 +65,14 @@ mod no_std {
     }
 
     fn render_error(message: &str) {
+        let theme = theme::current();
         display::draw_string(
             message,
             SCREEN_WIDTH / 2 - message.len() as u16 * CHAR_WIDTH / 2,
             SCREEN_HEIGHT / 2 - CHAR_HEIGHT / 2,
             false,
-            Color::RED,
-            Color::WHITE,
+            theme.error,
+            theme.background,
         );
     }
 }